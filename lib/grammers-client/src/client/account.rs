@@ -0,0 +1,276 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Methods to edit the profile and settings of the logged-in account.
+
+use super::Client;
+use crate::types::{Photo, PrivacyKey, PrivacyRule, PrivacyRules, Uploaded, User};
+pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_tl_types as tl;
+
+impl Client {
+    /// Change the first and last name of the account.
+    ///
+    /// The first name cannot be empty. Pass an empty last name to remove it.
+    ///
+    /// Returns the updated account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let me = client.set_name("Jane", "Doe").await?;
+    /// assert_eq!(me.full_name(), "Jane Doe");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_name(
+        &self,
+        first_name: &str,
+        last_name: &str,
+    ) -> Result<User, InvocationError> {
+        self.update_profile(tl::functions::account::UpdateProfile {
+            first_name: Some(first_name.to_string()),
+            last_name: Some(last_name.to_string()),
+            about: None,
+        })
+        .await
+    }
+
+    /// Change the bio of the account (also known as "about"). Pass an empty string to remove it.
+    ///
+    /// Returns the updated account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.set_bio("Powered by grammers").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_bio(&self, bio: &str) -> Result<User, InvocationError> {
+        self.update_profile(tl::functions::account::UpdateProfile {
+            first_name: None,
+            last_name: None,
+            about: Some(bio.to_string()),
+        })
+        .await
+    }
+
+    async fn update_profile(
+        &self,
+        request: tl::functions::account::UpdateProfile,
+    ) -> Result<User, InvocationError> {
+        self.invoke(&request).await.map(User::from_raw)
+    }
+
+    /// Check whether a username is available to be used by the account.
+    ///
+    /// Fails with `USERNAME_INVALID` if the username does not meet the requirements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// if client.check_username("jane_doe").await? {
+    ///     client.set_username(Some("jane_doe")).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_username(&self, username: &str) -> Result<bool, InvocationError> {
+        self.invoke(&tl::functions::account::CheckUsername {
+            username: username.to_string(),
+        })
+        .await
+    }
+
+    /// Change the main username of the account, or remove it with `None`.
+    ///
+    /// Returns the updated account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.set_username(None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_username(&self, username: Option<&str>) -> Result<User, InvocationError> {
+        self.invoke(&tl::functions::account::UpdateUsername {
+            username: username.unwrap_or_default().to_string(),
+        })
+        .await
+        .map(User::from_raw)
+    }
+
+    /// Show or hide one of the additional usernames owned by the account (such as collectible
+    /// usernames) in its profile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.toggle_username("jane", true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn toggle_username(
+        &self,
+        username: &str,
+        active: bool,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::account::ToggleUsername {
+            username: username.to_string(),
+            active,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Change the order in which the active usernames of the account are shown in its profile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.reorder_usernames(&["jane", "jane_doe"]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reorder_usernames(&self, usernames: &[&str]) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::account::ReorderUsernames {
+            order: usernames.iter().map(|u| u.to_string()).collect(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Set a previously-uploaded photo as the profile photo of the account.
+    ///
+    /// Returns the new profile photo.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let uploaded = client.upload_file("avatar.jpg").await?;
+    /// client.set_profile_photo(uploaded).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_profile_photo(&self, photo: Uploaded) -> Result<Photo, InvocationError> {
+        let tl::enums::photos::Photo::Photo(photo) = self
+            .invoke(&tl::functions::photos::UploadProfilePhoto {
+                fallback: false,
+                bot: None,
+                file: Some(photo.raw),
+                video: None,
+                video_start_ts: None,
+                video_emoji_markup: None,
+            })
+            .await?;
+        Ok(Photo::from_raw(photo.photo))
+    }
+
+    /// Delete profile photos of the account, such as those returned by
+    /// [`Client::iter_profile_photos`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let me = client.get_me().await?;
+    /// let mut photos = client.iter_profile_photos(&me);
+    /// if let Some(photo) = photos.next().await? {
+    ///     client.delete_profile_photos(&[photo]).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_profile_photos(&self, photos: &[Photo]) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::photos::DeletePhotos {
+            id: photos
+                .iter()
+                .map(|photo| photo.to_raw_input_media().id)
+                .collect(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Change whether the account appears as online or offline to others, as allowed by its
+    /// [`PrivacyKey::LastSeen`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.set_online(true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_online(&self, online: bool) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::account::UpdateStatus { offline: !online })
+            .await
+            .map(drop)
+    }
+
+    /// Get the rules of a privacy setting of the account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::PrivacyKey;
+    ///
+    /// for rule in client.get_privacy(PrivacyKey::PhoneNumber).await? {
+    ///     println!("{:?}", rule);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_privacy(&self, key: PrivacyKey) -> Result<Vec<PrivacyRule>, InvocationError> {
+        let tl::enums::account::PrivacyRules::Rules(rules) = self
+            .invoke(&tl::functions::account::GetPrivacy { key: key.to_raw() })
+            .await?;
+        Ok(rules.rules.into_iter().map(PrivacyRule::from_raw).collect())
+    }
+
+    /// Replace the rules of a privacy setting of the account.
+    ///
+    /// Returns the rules in effect after the change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::{PrivacyKey, PrivacyRules};
+    ///
+    /// client
+    ///     .set_privacy(PrivacyKey::LastSeen, PrivacyRules::contacts().allow_premium())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_privacy(
+        &self,
+        key: PrivacyKey,
+        rules: PrivacyRules,
+    ) -> Result<Vec<PrivacyRule>, InvocationError> {
+        let tl::enums::account::PrivacyRules::Rules(rules) = self
+            .invoke(&tl::functions::account::SetPrivacy {
+                key: key.to_raw(),
+                rules: rules.to_raw(),
+            })
+            .await?;
+        Ok(rules.rules.into_iter().map(PrivacyRule::from_raw).collect())
+    }
+}
@@ -7,12 +7,28 @@
 // except according to those terms.
 use super::Client;
 use super::net::connect_sender;
-use crate::types::{LoginToken, PasswordToken, TermsOfService, User};
+use crate::types::{
+    Authorization, IterBuffer, LoginCodeSettings, LoginToken, NewPassword, PasswordToken,
+    QrLoginToken, SentCodeType, TermsOfService, User,
+};
 use crate::utils;
-use grammers_crypto::two_factor_auth::{calculate_2fa, check_p_and_g};
+use futures_util::future::{Either, select};
+use grammers_crypto::two_factor_auth::{
+    calculate_2fa, calculate_password_hash, check_p_and_g, generate_salt1,
+};
+use grammers_mtproto::mtp;
+use grammers_mtsender::ReadError;
+use grammers_mtsender::utils::sleep_until;
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
-use grammers_tl_types as tl;
+use grammers_tl_types::{self as tl, Identifiable};
+use log::{debug, warn};
 use std::fmt;
+use std::pin::pin;
+use std::time::Duration;
+use web_time::{Instant, SystemTime};
+
+/// The minimum time to wait for a QR login token to be accepted before exporting a new one.
+const MIN_LOGIN_TOKEN_WAIT: Duration = Duration::from_secs(5);
 
 /// The error type which is returned when signing in fails.
 #[derive(Debug)]
@@ -33,10 +49,7 @@ impl fmt::Display for SignInError {
         match self {
             SignUpRequired {
                 terms_of_service: tos,
-            } => write!(
-                f,
-                "sign in error: sign up with official client required: {tos:?}"
-            ),
+            } => write!(f, "sign in error: sign up required: {tos:?}"),
             PasswordRequired(_password) => write!(f, "2fa password required"),
             InvalidCode => write!(f, "sign in error: invalid code"),
             InvalidPassword => write!(f, "invalid password"),
@@ -47,6 +60,147 @@ impl fmt::Display for SignInError {
 
 impl std::error::Error for SignInError {}
 
+/// The error type which is returned when managing the two-factor authentication password fails.
+#[derive(Debug)]
+pub enum PasswordError {
+    /// The current password was not correct.
+    InvalidPassword,
+    /// The code used to confirm the recovery email was not correct.
+    InvalidCode,
+    Other(InvocationError),
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PasswordError::*;
+        match self {
+            InvalidPassword => write!(f, "password error: invalid password"),
+            InvalidCode => write!(f, "password error: invalid code"),
+            Other(e) => write!(f, "password error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+/// The result of successfully changing the two-factor authentication password settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PasswordUpdate {
+    /// The new settings are already in effect.
+    Applied,
+    /// The settings were saved, but the recovery email must be confirmed with
+    /// [`Client::confirm_recovery_email`] before they take effect.
+    EmailUnconfirmed { code_length: i32 },
+}
+
+/// The error used when Telegram responds to a login step with something other than what the step
+/// can produce, such as being logged in straight away when only a code was requested.
+fn unexpected_response(id: u32) -> InvocationError {
+    InvocationError::Read(ReadError::Deserialize(
+        mtp::DeserializeError::UnexpectedConstructor { id },
+    ))
+}
+
+/// The purpose of verifying an email while logging in with the given token.
+fn login_setup_purpose(token: &LoginToken) -> tl::enums::EmailVerifyPurpose {
+    tl::types::EmailVerifyPurposeLoginSetup {
+        phone_number: token.phone.clone(),
+        phone_code_hash: token.phone_code_hash.clone(),
+    }
+    .into()
+}
+
+pub type AuthorizationIter = IterBuffer<tl::functions::account::GetAuthorizations, Authorization>;
+
+impl AuthorizationIter {
+    fn new(client: &Client) -> Self {
+        Self::from_request(client, 0, tl::functions::account::GetAuthorizations {})
+    }
+
+    /// Determines how many sessions there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.fill_buffer().await?;
+        Ok(self.total.unwrap())
+    }
+
+    /// Fetch all the sessions at once, since the request is not paginated.
+    async fn fill_buffer(&mut self) -> Result<(), InvocationError> {
+        let tl::enums::account::Authorizations::Authorizations(authorizations) =
+            self.client.invoke(&self.request).await?;
+
+        self.last_chunk = true;
+        self.total = Some(authorizations.authorizations.len());
+        self.buffer.extend(
+            authorizations
+                .authorizations
+                .into_iter()
+                .map(Authorization::from_raw),
+        );
+        Ok(())
+    }
+
+    /// Return the next `Authorization` from the internal buffer, filling the buffer previously if
+    /// it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no sessions left.
+    pub async fn next(&mut self) -> Result<Option<Authorization>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.fill_buffer().await?;
+        Ok(self.pop_item())
+    }
+}
+
+/// Build the settings needed to set a new password, hashed with the algorithm Telegram requested.
+///
+/// Fails if the algorithm is not supported or its parameters are not safe to use.
+fn new_password_settings(
+    password_token: &PasswordToken,
+    new_password: &NewPassword,
+) -> Result<tl::types::account::PasswordInputSettings, InvocationError> {
+    let tl::enums::PasswordKdfAlgo::Sha256Sha256Pbkdf2Hmacsha512iter100000Sha256ModPow(
+        tl::types::PasswordKdfAlgoSha256Sha256Pbkdf2Hmacsha512iter100000Sha256ModPow {
+            salt1,
+            salt2,
+            p,
+            g,
+        },
+    ) = &password_token.password.new_algo
+    else {
+        return Err(utils::bad_request("NEW_SETTINGS_INVALID"));
+    };
+    if !check_p_and_g(p, g) {
+        return Err(utils::bad_request("NEW_SETTINGS_INVALID"));
+    }
+
+    let salt1 = generate_salt1(salt1);
+    let new_password_hash = calculate_password_hash(&salt1, salt2, p, g, &new_password.password);
+
+    Ok(tl::types::account::PasswordInputSettings {
+        new_algo: Some(
+            tl::types::PasswordKdfAlgoSha256Sha256Pbkdf2Hmacsha512iter100000Sha256ModPow {
+                salt1,
+                salt2: salt2.clone(),
+                g: *g,
+                p: p.clone(),
+            }
+            .into(),
+        ),
+        new_password_hash: Some(new_password_hash.to_vec()),
+        hint: Some(new_password.hint.clone()),
+        email: new_password.email.clone(),
+        new_secure_settings: None,
+    })
+}
+
 /// Method implementations related with the authentication of the user into the API.
 ///
 /// Most requests to the API require the user to have authorized their key, stored in the session,
@@ -85,7 +239,10 @@ impl Client {
     ) -> Result<User, InvocationError> {
         // In the extremely rare case where `Err` happens, there's not much we can do.
         // `message_box` will try to correct its state as updates arrive.
-        let update_state = self.invoke(&tl::functions::updates::GetState {}).await.ok();
+        let update_state = self
+            .invoke_unlimited(&tl::functions::updates::GetState {})
+            .await
+            .ok();
 
         let user = User::from_raw(auth.user);
 
@@ -112,6 +269,18 @@ impl Client {
         Ok(user)
     }
 
+    /// Connect to a different datacenter, which becomes the home datacenter of this client.
+    ///
+    /// Since the account is not logged in yet, there's no need to export the current
+    /// authorization. A new authorization key will be generated if needed.
+    async fn migrate_to(&self, dc_id: i32) -> Result<(), AuthorizationError> {
+        let (sender, request_tx) =
+            connect_sender(dc_id, &self.0.config, &self.0.dc_options).await?;
+        self.0.conn.replace(sender, request_tx).await;
+        self.0.state.write().unwrap().dc_id = dc_id;
+        Ok(())
+    }
+
     /// Signs in to the bot account associated with this token.
     ///
     /// This is the method you need to call to use the client under a bot account.
@@ -156,14 +325,7 @@ impl Client {
         let result = match self.invoke(&request).await {
             Ok(x) => x,
             Err(InvocationError::Rpc(err)) if err.code == 303 => {
-                let dc_id = err.value.unwrap() as i32;
-                let (sender, request_tx) = connect_sender(dc_id, &self.0.config).await?;
-                {
-                    *self.0.conn.sender.lock().await = sender;
-                    *self.0.conn.request_tx.write().unwrap() = request_tx;
-                    let mut state = self.0.state.write().unwrap();
-                    state.dc_id = dc_id;
-                }
+                self.migrate_to(err.value.unwrap() as i32).await?;
                 self.invoke(&request).await?
             }
             Err(e) => return Err(e.into()),
@@ -204,22 +366,39 @@ impl Client {
     /// # }
     /// ```
     pub async fn request_login_code(&self, phone: &str) -> Result<LoginToken, AuthorizationError> {
+        self.request_login_code_with_settings(phone, LoginCodeSettings::default())
+            .await
+    }
+
+    /// Like [`Client::request_login_code`], but with the given settings, which determine the
+    /// ways in which the code may be delivered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # const PHONE: &str = "";
+    /// use grammers_client::types::LoginCodeSettings;
+    ///
+    /// let settings = LoginCodeSettings::default()
+    ///     .allow_missed_call(true)
+    ///     .current_number(true);
+    ///
+    /// let token = client.request_login_code_with_settings(PHONE, settings).await?;
+    /// println!("code sent via {:?}", token.code_type());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_login_code_with_settings(
+        &self,
+        phone: &str,
+        settings: LoginCodeSettings,
+    ) -> Result<LoginToken, AuthorizationError> {
         let request = tl::functions::auth::SendCode {
             phone_number: phone.to_string(),
             api_id: self.0.config.api_id,
             api_hash: self.0.config.api_hash.clone(),
-            settings: tl::types::CodeSettings {
-                allow_flashcall: false,
-                current_number: false,
-                allow_app_hash: false,
-                allow_missed_call: false,
-                allow_firebase: false,
-                logout_tokens: None,
-                token: None,
-                app_sandbox: None,
-                unknown_number: false,
-            }
-            .into(),
+            settings: settings.to_raw(),
         };
 
         use tl::enums::auth::SentCode as SC;
@@ -227,7 +406,12 @@ impl Client {
         let sent_code: tl::types::auth::SentCode = match self.invoke(&request).await {
             Ok(x) => match x {
                 SC::Code(code) => code,
-                SC::Success(_) => panic!("should not have logged in yet"),
+                SC::Success(_) => {
+                    return Err(unexpected_response(
+                        tl::types::auth::SentCodeSuccess::CONSTRUCTOR_ID,
+                    )
+                    .into());
+                }
             },
             Err(InvocationError::Rpc(err)) if err.code == 303 => {
                 // Since we are not logged in (we're literally requesting for
@@ -236,33 +420,186 @@ impl Client {
                 //
                 // Just connect and generate a new authorization key with it
                 // before trying again.
-                let dc_id = err.value.unwrap() as i32;
-                let (sender, request_tx) = connect_sender(dc_id, &self.0.config).await?;
-                {
-                    *self.0.conn.sender.lock().await = sender;
-                    *self.0.conn.request_tx.write().unwrap() = request_tx;
-                    let mut state = self.0.state.write().unwrap();
-                    state.dc_id = dc_id;
-                }
+                self.migrate_to(err.value.unwrap() as i32).await?;
                 match self.invoke(&request).await? {
                     SC::Code(code) => code,
-                    SC::Success(_) => panic!("should not have logged in yet"),
+                    SC::Success(_) => {
+                        return Err(unexpected_response(
+                            tl::types::auth::SentCodeSuccess::CONSTRUCTOR_ID,
+                        )
+                        .into());
+                    }
                 }
             }
             Err(e) => return Err(e.into()),
         };
 
-        Ok(LoginToken {
-            phone: phone.to_string(),
-            phone_code_hash: sent_code.phone_code_hash,
+        Ok(LoginToken::from_raw(phone.to_string(), sent_code))
+    }
+
+    /// Requests the login code to be sent again, using the next method of delivery if any, such
+    /// as a phone call instead of SMS.
+    ///
+    /// The [`LoginToken::next_type`] indicates how the new code will be sent, and
+    /// [`LoginToken::timeout`] how long to wait before requesting it. The returned token replaces
+    /// the previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # const PHONE: &str = "";
+    /// let token = client.request_login_code(PHONE).await?;
+    ///
+    /// // ... the code never arrived ...
+    ///
+    /// if token.next_type().is_some() {
+    ///     let token = client.resend_login_code(&token).await?;
+    ///     println!("code sent via {:?}", token.code_type());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resend_login_code(
+        &self,
+        token: &LoginToken,
+    ) -> Result<LoginToken, InvocationError> {
+        let sent_code = self
+            .invoke(&tl::functions::auth::ResendCode {
+                phone_number: token.phone.clone(),
+                phone_code_hash: token.phone_code_hash.clone(),
+                reason: None,
+            })
+            .await?;
+
+        match sent_code {
+            tl::enums::auth::SentCode::Code(code) => {
+                Ok(LoginToken::from_raw(token.phone.clone(), code))
+            }
+            tl::enums::auth::SentCode::Success(_) => Err(unexpected_response(
+                tl::types::auth::SentCodeSuccess::CONSTRUCTOR_ID,
+            )),
+        }
+    }
+
+    /// Cancels the login code that was requested, so that it can no longer be used to sign in.
+    ///
+    /// This should be used when the user decides not to complete the login, such as if they
+    /// entered the wrong phone number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # const PHONE: &str = "";
+    /// let token = client.request_login_code(PHONE).await?;
+    /// client.cancel_login_code(&token).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn cancel_login_code(&self, token: &LoginToken) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::auth::CancelCode {
+            phone_number: token.phone.clone(),
+            phone_code_hash: token.phone_code_hash.clone(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Sends a code to verify the given email, which will be used to receive login codes from now
+    /// on.
+    ///
+    /// This is needed when the [`LoginToken::code_type`] is [`SentCodeType::SetUpEmailRequired`].
+    /// The returned [`SentCodeType::Email`] describes the code sent, which must then be used with
+    /// [`Client::verify_login_email`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # const PHONE: &str = "";
+    /// use grammers_client::types::SentCodeType;
+    ///
+    /// fn ask_code_to_user() -> String {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let mut token = client.request_login_code(PHONE).await?;
+    /// if *token.code_type() == SentCodeType::SetUpEmailRequired {
+    ///     client.request_login_email_code(&token, "alice@example.com").await?;
+    ///     token = client.verify_login_email(&token, &ask_code_to_user()).await?;
+    /// }
+    ///
+    /// // The login code can now be used to sign in with the new token.
+    /// let user = client.sign_in(&token, &ask_code_to_user()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_login_email_code(
+        &self,
+        token: &LoginToken,
+        email: &str,
+    ) -> Result<SentCodeType, InvocationError> {
+        let tl::enums::account::SentEmailCode::Code(sent) = self
+            .invoke(&tl::functions::account::SendVerifyEmailCode {
+                purpose: login_setup_purpose(token),
+                email: email.to_string(),
+            })
+            .await?;
+
+        Ok(SentCodeType::Email {
+            email_pattern: sent.email_pattern,
+            length: sent.length,
         })
     }
 
+    /// Verifies the login email set up with [`Client::request_login_email_code`], using the code
+    /// that was sent to it.
+    ///
+    /// Once verified, Telegram sends the login code, and the returned token must be used to
+    /// [`Client::sign_in`] instead of the previous one.
+    pub async fn verify_login_email(
+        &self,
+        token: &LoginToken,
+        code: &str,
+    ) -> Result<LoginToken, SignInError> {
+        let verified = match self
+            .invoke(&tl::functions::account::VerifyEmail {
+                purpose: login_setup_purpose(token),
+                verification: tl::types::EmailVerificationCode {
+                    code: code.to_string(),
+                }
+                .into(),
+            })
+            .await
+        {
+            Ok(verified) => verified,
+            Err(err) if err.is("EMAIL_CODE_INVALID") => return Err(SignInError::InvalidCode),
+            Err(err) => return Err(SignInError::Other(err)),
+        };
+
+        match verified {
+            tl::enums::account::EmailVerified::Login(tl::types::account::EmailVerifiedLogin {
+                sent_code: tl::enums::auth::SentCode::Code(code),
+                ..
+            }) => Ok(LoginToken::from_raw(token.phone.clone(), code)),
+            tl::enums::account::EmailVerified::Login(_) => Err(SignInError::Other(
+                unexpected_response(tl::types::auth::SentCodeSuccess::CONSTRUCTOR_ID),
+            )),
+            tl::enums::account::EmailVerified::Verified(_) => Err(SignInError::Other(
+                unexpected_response(tl::types::account::EmailVerified::CONSTRUCTOR_ID),
+            )),
+        }
+    }
+
     /// Signs in to the user account.
     ///
     /// You must call [`Client::request_login_code`] before using this method in order to obtain
     /// necessary login token, and also have asked the user for the login code.
     ///
+    /// If the code was sent to the login email of the account, as indicated by
+    /// [`SentCodeType::Email`], the code is used to verify said email instead.
+    ///
     /// It is recommended to save the [`Client::session()`] on successful login, and if saving
     /// fails, it is recommended to [`Client::sign_out`]. If the session cannot be saved, then the
     /// authorization will be "lost" in the list of logged-in clients, since it is unaccessible.
@@ -300,12 +637,25 @@ impl Client {
     /// # }
     /// ```
     pub async fn sign_in(&self, token: &LoginToken, code: &str) -> Result<User, SignInError> {
+        let (phone_code, email_verification) = match token.code_type {
+            SentCodeType::Email { .. } => (
+                None,
+                Some(
+                    tl::types::EmailVerificationCode {
+                        code: code.to_string(),
+                    }
+                    .into(),
+                ),
+            ),
+            _ => (Some(code.to_string()), None),
+        };
+
         match self
             .invoke(&tl::functions::auth::SignIn {
                 phone_number: token.phone.clone(),
                 phone_code_hash: token.phone_code_hash.clone(),
-                phone_code: Some(code.to_string()),
-                email_verification: None,
+                phone_code,
+                email_verification,
             })
             .await
         {
@@ -317,21 +667,289 @@ impl Client {
                     terms_of_service: x.terms_of_service.map(TermsOfService::from_raw),
                 })
             }
-            Err(err) if err.is("SESSION_PASSWORD_NEEDED") => {
-                let password_token = self.get_password_information().await;
-                match password_token {
-                    Ok(token) => Err(SignInError::PasswordRequired(token)),
-                    Err(e) => Err(SignInError::Other(e)),
-                }
+            Err(err) if err.is("SESSION_PASSWORD_NEEDED") => Err(self.password_required().await),
+            Err(err) if err.is("PHONE_CODE_*") || err.is("EMAIL_CODE_INVALID") => {
+                Err(SignInError::InvalidCode)
             }
-            Err(err) if err.is("PHONE_CODE_*") => Err(SignInError::InvalidCode),
             Err(error) => Err(SignInError::Other(error)),
         }
     }
 
-    /// Extract information needed for the two-factor authentication
-    /// It's called automatically when we get SESSION_PASSWORD_NEEDED error during sign in.
-    async fn get_password_information(&self) -> Result<PasswordToken, InvocationError> {
+    /// Signs up a new user account, after [`Client::sign_in`] failed with
+    /// [`SignInError::SignUpRequired`] because the phone number is not registered yet.
+    ///
+    /// The same [`LoginToken`] and code used to sign in are used to sign up, so the code must not
+    /// have expired yet. The `last_name` may be empty.
+    ///
+    /// By signing up, you agree to Telegram's [`TermsOfService`], which should be shown to the
+    /// user beforehand. The `terms_of_service` that came with [`SignInError::SignUpRequired`]
+    /// are accepted once the account is created. The account remains signed in even if accepting
+    /// them fails, in which case they can be accepted later with
+    /// [`Client::accept_terms_of_service`].
+    ///
+    /// It is recommended to save the [`Client::session()`] on successful sign up, and if saving
+    /// fails, it is recommended to [`Client::sign_out`]. If the session cannot be saved, then the
+    /// authorization will be "lost" in the list of logged-in clients, since it is unaccessible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grammers_client::SignInError;
+    ///
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # const PHONE: &str = "";
+    /// # let code = "";
+    /// let token = client.request_login_code(PHONE).await?;
+    ///
+    /// let user = match client.sign_in(&token, &code).await {
+    ///     Ok(user) => user,
+    ///     Err(SignInError::SignUpRequired { terms_of_service }) => {
+    ///         if let Some(tos) = &terms_of_service {
+    ///             println!("{}", tos.text());
+    ///         }
+    ///         client
+    ///             .sign_up(&token, "Alice", "", terms_of_service.as_ref())
+    ///             .await?
+    ///     }
+    ///     Err(err) => return Err(err.into()),
+    /// };
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sign_up(
+        &self,
+        token: &LoginToken,
+        first_name: &str,
+        last_name: &str,
+        terms_of_service: Option<&TermsOfService>,
+    ) -> Result<User, SignInError> {
+        let result = self
+            .invoke(&tl::functions::auth::SignUp {
+                no_joined_notifications: false,
+                phone_number: token.phone.clone(),
+                phone_code_hash: token.phone_code_hash.clone(),
+                first_name: first_name.to_string(),
+                last_name: last_name.to_string(),
+            })
+            .await;
+
+        let user = match result {
+            Ok(tl::enums::auth::Authorization::Authorization(x)) => {
+                self.complete_login(x).await.map_err(SignInError::Other)?
+            }
+            Ok(tl::enums::auth::Authorization::SignUpRequired(x)) => {
+                return Err(SignInError::SignUpRequired {
+                    terms_of_service: x.terms_of_service.map(TermsOfService::from_raw),
+                });
+            }
+            Err(err) if err.is("PHONE_CODE_*") => return Err(SignInError::InvalidCode),
+            Err(err) => return Err(SignInError::Other(err)),
+        };
+
+        // The account exists and is logged in at this point, so failing to accept the terms
+        // must not be reported as a failure to sign up.
+        if let Some(terms_of_service) = terms_of_service
+            && let Err(err) = self.accept_terms_of_service(terms_of_service).await
+        {
+            warn!("failed to accept the terms of service after signing up: {err}");
+        }
+
+        Ok(user)
+    }
+
+    /// Accepts the given Terms of Service, which is required after they change before the
+    /// account can keep using the service.
+    ///
+    /// [`Client::sign_up`] already does this for new accounts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client, tos: grammers_client::types::TermsOfService) -> Result<(), Box<dyn std::error::Error>> {
+    /// println!("{}", tos.text());
+    /// client.accept_terms_of_service(&tos).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn accept_terms_of_service(
+        &self,
+        terms_of_service: &TermsOfService,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::help::AcceptTermsOfService {
+            id: terms_of_service.raw.id.clone(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Signs in to the user account by displaying a QR code, which must be scanned by a Telegram
+    /// application where the account is already logged in (under Settings > Devices).
+    ///
+    /// This is useful when the login code cannot be received, such as when running on a headless
+    /// server. The `on_token` callback is invoked with a new [`QrLoginToken`] every time one is
+    /// produced. Its [URL](QrLoginToken::url) should be rendered as a QR code and shown to the
+    /// user, replacing any previous one. Tokens are refreshed automatically when they expire, so
+    /// this method only returns once the code is scanned and accepted, or if an error occurs.
+    ///
+    /// `except_ids` are the identifiers of the users already logged in to this application, if
+    /// any, so that they cannot scan the code to log in again.
+    ///
+    /// If the account has two-factor authentication enabled, [`SignInError::PasswordRequired`]
+    /// is returned, and [`Client::check_password`] must be used to complete the login.
+    ///
+    /// It is recommended to save the [`Client::session()`] on successful login, and if saving
+    /// fails, it is recommended to [`Client::sign_out`]. If the session cannot be saved, then the
+    /// authorization will be "lost" in the list of logged-in clients, since it is unaccessible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grammers_client::SignInError;
+    ///
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// fn show_qr_code(url: &str) {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let user = match client.qr_login(&[], |token| show_qr_code(&token.url())).await {
+    ///     Ok(user) => user,
+    ///     Err(SignInError::PasswordRequired(password_token)) => {
+    ///         client.check_password(password_token, "hunter2").await?
+    ///     }
+    ///     Err(err) => return Err(err.into()),
+    /// };
+    ///
+    /// println!("Signed in as {}!", user.full_name());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn qr_login<F: FnMut(&QrLoginToken)>(
+        &self,
+        except_ids: &[i64],
+        mut on_token: F,
+    ) -> Result<User, SignInError> {
+        let request = tl::functions::auth::ExportLoginToken {
+            api_id: self.0.config.api_id,
+            api_hash: self.0.config.api_hash.clone(),
+            except_ids: except_ids.to_vec(),
+        };
+
+        // Any previous update is not relevant to this login attempt.
+        self.0.state.write().unwrap().login_token_updated = false;
+
+        loop {
+            let mut result = self.invoke_login_token(&request).await?;
+
+            // The account may live in a different datacenter, where the token must be imported.
+            if let tl::enums::auth::LoginToken::MigrateTo(migrate) = result {
+                self.migrate_to(migrate.dc_id).await.map_err(|e| match e {
+                    AuthorizationError::Invoke(e) => SignInError::Other(e),
+                    AuthorizationError::Gen(e) => {
+                        panic!("authorization key generation failed: {e}")
+                    }
+                })?;
+                result = self
+                    .invoke_login_token(&tl::functions::auth::ImportLoginToken {
+                        token: migrate.token,
+                    })
+                    .await?;
+            }
+
+            use tl::enums::auth::Authorization as A;
+            match result {
+                tl::enums::auth::LoginToken::Token(token) => {
+                    let token = QrLoginToken {
+                        token: token.token,
+                        expires: token.expires,
+                    };
+                    on_token(&token);
+                    self.wait_login_token(&token)
+                        .await
+                        .map_err(|e| SignInError::Other(e.into()))?;
+                }
+                tl::enums::auth::LoginToken::MigrateTo(_) => {
+                    warn!("login token migrated twice; exporting it again");
+                }
+                tl::enums::auth::LoginToken::Success(success) => {
+                    return match success.authorization {
+                        A::Authorization(x) => {
+                            self.complete_login(x).await.map_err(SignInError::Other)
+                        }
+                        A::SignUpRequired(x) => Err(SignInError::SignUpRequired {
+                            terms_of_service: x.terms_of_service.map(TermsOfService::from_raw),
+                        }),
+                    };
+                }
+            }
+        }
+    }
+
+    /// Invoke a request that exports or imports a login token, mapping the errors.
+    async fn invoke_login_token<R: tl::RemoteCall<Return = tl::enums::auth::LoginToken>>(
+        &self,
+        request: &R,
+    ) -> Result<tl::enums::auth::LoginToken, SignInError> {
+        match self.invoke(request).await {
+            Ok(result) => Ok(result),
+            Err(err) if err.is("SESSION_PASSWORD_NEEDED") => Err(self.password_required().await),
+            Err(err) => Err(SignInError::Other(err)),
+        }
+    }
+
+    /// Process network events until the login token is accepted or it expires.
+    async fn wait_login_token(&self, token: &QrLoginToken) -> Result<(), ReadError> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("system time is before epoch")
+            .as_secs() as i64;
+        // Wait a bit even if the token seems to have expired already, in case the local clock is
+        // ahead, to avoid exporting new tokens in a loop.
+        let expires_in = Duration::from_secs((token.expires as i64 - now).max(0) as u64)
+            .max(MIN_LOGIN_TOKEN_WAIT);
+        let deadline = Instant::now() + expires_in;
+
+        loop {
+            if std::mem::take(&mut self.0.state.write().unwrap().login_token_updated) {
+                debug!("login token was accepted");
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                debug!("login token expired");
+                return Ok(());
+            }
+            match select(pin!(self.step()), pin!(sleep_until(deadline))).await {
+                Either::Left((result, _)) => result?,
+                Either::Right(_) => {}
+            }
+        }
+    }
+
+    /// Build the error returned when the account has two-factor authentication enabled.
+    async fn password_required(&self) -> SignInError {
+        match self.get_password_token().await {
+            Ok(token) => SignInError::PasswordRequired(token),
+            Err(e) => SignInError::Other(e),
+        }
+    }
+
+    /// Fetches the current two-factor authentication settings of the account.
+    ///
+    /// This is done automatically when the password is required to sign in, but the token is
+    /// also needed to manage the password of an account that is already logged in, such as with
+    /// [`Client::change_password`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let token = client.get_password_token().await?;
+    /// if token.has_password() {
+    ///     println!("The account has a password with hint {:?}", token.hint());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_password_token(&self) -> Result<PasswordToken, InvocationError> {
         let request = tl::functions::account::GetPassword {};
 
         let password: tl::types::account::Password = self.invoke(&request).await?.into();
@@ -382,17 +1000,41 @@ impl Client {
         password_token: PasswordToken,
         password: impl AsRef<[u8]>,
     ) -> Result<User, SignInError> {
+        let check_password = tl::functions::auth::CheckPassword {
+            password: self
+                .password_check(password_token, password)
+                .await
+                .map_err(SignInError::Other)?,
+        };
+
+        match self.invoke(&check_password).await {
+            Ok(tl::enums::auth::Authorization::Authorization(x)) => {
+                self.complete_login(x).await.map_err(SignInError::Other)
+            }
+            Ok(tl::enums::auth::Authorization::SignUpRequired(_x)) => panic!("Unexpected result"),
+            Err(err) if err.is("PASSWORD_HASH_INVALID") => Err(SignInError::InvalidPassword),
+            Err(error) => Err(SignInError::Other(error)),
+        }
+    }
+
+    /// Compute the proof that the user knows the password of the account, without sending the
+    /// password itself.
+    async fn password_check(
+        &self,
+        password_token: PasswordToken,
+        password: impl AsRef<[u8]>,
+    ) -> Result<tl::enums::InputCheckPasswordSrp, InvocationError> {
         let mut password_info = password_token.password;
-        let current_algo = password_info.current_algo.unwrap();
+        if !password_info.has_password {
+            return Ok(tl::enums::InputCheckPasswordSrp::InputCheckPasswordEmpty);
+        }
+
+        let current_algo = password_info.current_algo.clone().unwrap();
         let mut params = utils::extract_password_parameters(&current_algo);
 
         // Telegram sent us incorrect parameters, trying to get them again
         if !check_p_and_g(params.2, params.3) {
-            password_info = self
-                .get_password_information()
-                .await
-                .map_err(SignInError::Other)?
-                .password;
+            password_info = self.get_password_token().await?.password;
             params =
                 utils::extract_password_parameters(password_info.current_algo.as_ref().unwrap());
             if !check_p_and_g(params.2, params.3) {
@@ -402,25 +1044,289 @@ impl Client {
 
         let (salt1, salt2, p, g) = params;
 
-        let g_b = password_info.srp_b.unwrap();
-        let a: Vec<u8> = password_info.secure_random;
+        let g_b = password_info.srp_b.clone().unwrap();
+        let a: Vec<u8> = password_info.secure_random.clone();
 
         let (m1, g_a) = calculate_2fa(salt1, salt2, p, g, g_b, a, password);
 
-        let check_password = tl::functions::auth::CheckPassword {
-            password: tl::enums::InputCheckPasswordSrp::Srp(tl::types::InputCheckPasswordSrp {
-                srp_id: password_info.srp_id.unwrap(),
-                a: g_a.to_vec(),
-                m1: m1.to_vec(),
-            }),
+        Ok(tl::types::InputCheckPasswordSrp {
+            srp_id: password_info.srp_id.unwrap(),
+            a: g_a.to_vec(),
+            m1: m1.to_vec(),
+        }
+        .into())
+    }
+
+    /// Update the password settings of the account, after proving the current password.
+    async fn update_password_settings(
+        &self,
+        password_token: PasswordToken,
+        current_password: impl AsRef<[u8]>,
+        new_settings: tl::types::account::PasswordInputSettings,
+    ) -> Result<PasswordUpdate, PasswordError> {
+        let password = self
+            .password_check(password_token, current_password)
+            .await
+            .map_err(PasswordError::Other)?;
+
+        match self
+            .invoke(&tl::functions::account::UpdatePasswordSettings {
+                password,
+                new_settings: new_settings.into(),
+            })
+            .await
+        {
+            Ok(_) => Ok(PasswordUpdate::Applied),
+            Err(err) if err.is("PASSWORD_HASH_INVALID") => Err(PasswordError::InvalidPassword),
+            Err(InvocationError::Rpc(err)) if err.is("EMAIL_UNCONFIRMED") => {
+                Ok(PasswordUpdate::EmailUnconfirmed {
+                    code_length: err.value.unwrap_or(0) as i32,
+                })
+            }
+            Err(err) => Err(PasswordError::Other(err)),
+        }
+    }
+
+    /// Sets a two-factor authentication password on an account that does not have one yet.
+    ///
+    /// If the new password has a recovery email, [`PasswordUpdate::EmailUnconfirmed`] is returned,
+    /// and the password will only take effect after [`Client::confirm_recovery_email`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::PasswordUpdate;
+    /// use grammers_client::types::NewPassword;
+    ///
+    /// fn ask_code_to_user() -> String {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let token = client.get_password_token().await?;
+    /// if !token.has_password() {
+    ///     let new_password = NewPassword::new("hunter2")
+    ///         .hint("the usual")
+    ///         .email("alice@example.com");
+    ///
+    ///     match client.set_password(token, new_password).await? {
+    ///         PasswordUpdate::Applied => {}
+    ///         PasswordUpdate::EmailUnconfirmed { .. } => {
+    ///             client.confirm_recovery_email(&ask_code_to_user()).await?;
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_password(
+        &self,
+        password_token: PasswordToken,
+        new_password: NewPassword,
+    ) -> Result<PasswordUpdate, PasswordError> {
+        let new_settings =
+            new_password_settings(&password_token, &new_password).map_err(PasswordError::Other)?;
+        self.update_password_settings(password_token, [], new_settings)
+            .await
+    }
+
+    /// Changes the two-factor authentication password of the account.
+    ///
+    /// If the new password has a recovery email, [`PasswordUpdate::EmailUnconfirmed`] is returned,
+    /// and the password will only take effect after [`Client::confirm_recovery_email`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::NewPassword;
+    ///
+    /// let token = client.get_password_token().await?;
+    /// client
+    ///     .change_password(token, "hunter2", NewPassword::new("hunter3"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn change_password(
+        &self,
+        password_token: PasswordToken,
+        current_password: impl AsRef<[u8]>,
+        new_password: NewPassword,
+    ) -> Result<PasswordUpdate, PasswordError> {
+        let new_settings =
+            new_password_settings(&password_token, &new_password).map_err(PasswordError::Other)?;
+        self.update_password_settings(password_token, current_password, new_settings)
+            .await
+    }
+
+    /// Removes the two-factor authentication password of the account, along with its recovery
+    /// email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let token = client.get_password_token().await?;
+    /// client.remove_password(token, "hunter2").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_password(
+        &self,
+        password_token: PasswordToken,
+        current_password: impl AsRef<[u8]>,
+    ) -> Result<(), PasswordError> {
+        let new_settings = tl::types::account::PasswordInputSettings {
+            new_algo: Some(tl::enums::PasswordKdfAlgo::Unknown),
+            new_password_hash: Some(Vec::new()),
+            hint: Some(String::new()),
+            email: Some(String::new()),
+            new_secure_settings: None,
         };
+        self.update_password_settings(password_token, current_password, new_settings)
+            .await
+            .map(drop)
+    }
 
-        match self.invoke(&check_password).await {
+    /// Sets or changes the recovery email of the account, without changing the password.
+    ///
+    /// This normally returns [`PasswordUpdate::EmailUnconfirmed`], because the email must be
+    /// confirmed with [`Client::confirm_recovery_email`] before it takes effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::PasswordUpdate;
+    ///
+    /// fn ask_code_to_user() -> String {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let token = client.get_password_token().await?;
+    /// let update = client
+    ///     .set_recovery_email(token, "hunter2", "alice@example.com")
+    ///     .await?;
+    ///
+    /// if let PasswordUpdate::EmailUnconfirmed { .. } = update {
+    ///     client.confirm_recovery_email(&ask_code_to_user()).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_recovery_email(
+        &self,
+        password_token: PasswordToken,
+        current_password: impl AsRef<[u8]>,
+        email: &str,
+    ) -> Result<PasswordUpdate, PasswordError> {
+        let new_settings = tl::types::account::PasswordInputSettings {
+            new_algo: None,
+            new_password_hash: None,
+            hint: None,
+            email: Some(email.to_string()),
+            new_secure_settings: None,
+        };
+        self.update_password_settings(password_token, current_password, new_settings)
+            .await
+    }
+
+    /// Confirms the recovery email that was set along with the password, using the code sent to
+    /// said email.
+    pub async fn confirm_recovery_email(&self, code: &str) -> Result<(), PasswordError> {
+        match self
+            .invoke(&tl::functions::account::ConfirmPasswordEmail {
+                code: code.to_string(),
+            })
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) if err.is("CODE_INVALID") || err.is("EMAIL_HASH_EXPIRED") => {
+                Err(PasswordError::InvalidCode)
+            }
+            Err(err) => Err(PasswordError::Other(err)),
+        }
+    }
+
+    /// Sends the code needed to [confirm the recovery email] again.
+    ///
+    /// [confirm the recovery email]: Client::confirm_recovery_email
+    pub async fn resend_recovery_email_code(&self) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::account::ResendPasswordEmail {})
+            .await?;
+        Ok(())
+    }
+
+    /// Requests a code to be sent to the recovery email of the account, which can be used to
+    /// [recover the password] if it was forgotten while signing in.
+    ///
+    /// Returns the pattern of the email the code was sent to.
+    ///
+    /// [recover the password]: Client::recover_password
+    pub async fn request_password_recovery(&self) -> Result<String, InvocationError> {
+        let tl::enums::auth::PasswordRecovery::Recovery(recovery) = self
+            .invoke(&tl::functions::auth::RequestPasswordRecovery {})
+            .await?;
+        Ok(recovery.email_pattern)
+    }
+
+    /// Signs in by resetting the forgotten password, using the code sent to the recovery email
+    /// after [`Client::request_password_recovery`].
+    ///
+    /// If a new password is given, it replaces the old one. Otherwise, the password is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grammers_client::SignInError;
+    ///
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// # let token = client.request_login_code("").await?;
+    /// # let code = "";
+    /// fn ask_code_to_user() -> String {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let user = match client.sign_in(&token, &code).await {
+    ///     Err(SignInError::PasswordRequired(password_token)) if password_token.has_recovery() => {
+    ///         let email = client.request_password_recovery().await?;
+    ///         println!("Code sent to {}", email);
+    ///         client
+    ///             .recover_password(password_token, &ask_code_to_user(), None)
+    ///             .await?
+    ///     }
+    ///     result => result?,
+    /// };
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn recover_password(
+        &self,
+        password_token: PasswordToken,
+        code: &str,
+        new_password: Option<NewPassword>,
+    ) -> Result<User, SignInError> {
+        let new_settings = new_password
+            .map(|new_password| new_password_settings(&password_token, &new_password))
+            .transpose()
+            .map_err(SignInError::Other)?
+            .map(Into::into);
+
+        match self
+            .invoke(&tl::functions::auth::RecoverPassword {
+                code: code.to_string(),
+                new_settings,
+            })
+            .await
+        {
             Ok(tl::enums::auth::Authorization::Authorization(x)) => {
                 self.complete_login(x).await.map_err(SignInError::Other)
             }
-            Ok(tl::enums::auth::Authorization::SignUpRequired(_x)) => panic!("Unexpected result"),
-            Err(err) if err.is("PASSWORD_HASH_INVALID") => Err(SignInError::InvalidPassword),
+            Ok(tl::enums::auth::Authorization::SignUpRequired(_)) => panic!("Unexpected result"),
+            Err(err) if err.is("CODE_INVALID") || err.is("PASSWORD_RECOVERY_EXPIRED") => {
+                Err(SignInError::InvalidCode)
+            }
             Err(error) => Err(SignInError::Other(error)),
         }
     }
@@ -450,6 +1356,111 @@ impl Client {
         self.invoke(&tl::functions::auth::LogOut {}).await
     }
 
+    /// Iterate over the active sessions where the account is logged in, including the current
+    /// one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut authorizations = client.iter_authorizations();
+    ///
+    /// while let Some(auth) = authorizations.next().await? {
+    ///     println!(
+    ///         "{} on {} from {} ({}), last active {}",
+    ///         auth.app_name(),
+    ///         auth.device_model(),
+    ///         auth.ip(),
+    ///         auth.country(),
+    ///         auth.date_active()
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_authorizations(&self) -> AuthorizationIter {
+        AuthorizationIter::new(self)
+    }
+
+    /// Terminates the session with the given [hash], logging it out.
+    ///
+    /// Telegram does not allow sessions created less than a day ago to terminate other sessions.
+    ///
+    /// [hash]: Authorization::hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut authorizations = client.iter_authorizations();
+    ///
+    /// while let Some(auth) = authorizations.next().await? {
+    ///     if !auth.is_official_app() {
+    ///         client.terminate_authorization(auth.hash()).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn terminate_authorization(&self, hash: i64) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::account::ResetAuthorization { hash })
+            .await?;
+        Ok(())
+    }
+
+    /// Terminates all the sessions of the account except for the current one.
+    ///
+    /// Telegram does not allow sessions created less than a day ago to terminate other sessions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.terminate_other_sessions().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn terminate_other_sessions(&self) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::auth::ResetAuthorizations {})
+            .await?;
+        Ok(())
+    }
+
+    /// Returns after how many days of inactivity sessions are terminated automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// println!("Inactive sessions expire after {} days", client.get_authorization_ttl_days().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_authorization_ttl_days(&self) -> Result<i32, InvocationError> {
+        let tl::enums::account::Authorizations::Authorizations(authorizations) = self
+            .invoke(&tl::functions::account::GetAuthorizations {})
+            .await?;
+        Ok(authorizations.authorization_ttl_days)
+    }
+
+    /// Changes after how many days of inactivity sessions are terminated automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.set_authorization_ttl_days(30).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_authorization_ttl_days(&self, days: i32) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::account::SetAuthorizationTtl {
+            authorization_ttl_days: days,
+        })
+        .await?;
+        Ok(())
+    }
+
     /// Synchronize all state to the session file and provide mutable access to it.
     ///
     /// You can use this to temporarily access the session and save it wherever you want to.
@@ -468,3 +1479,46 @@ impl Client {
         panic!("disconnect now only works via dropping");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password_token(new_algo: tl::enums::PasswordKdfAlgo) -> PasswordToken {
+        PasswordToken::new(tl::types::account::Password {
+            has_recovery: false,
+            has_secure_values: false,
+            has_password: false,
+            current_algo: None,
+            srp_b: None,
+            srp_id: None,
+            hint: None,
+            email_unconfirmed_pattern: None,
+            new_algo,
+            new_secure_algo: tl::enums::SecurePasswordKdfAlgo::Unknown,
+            secure_random: Vec::new(),
+            pending_reset_date: None,
+            login_email_pattern: None,
+        })
+    }
+
+    #[test]
+    fn new_password_with_unknown_algo() {
+        let token = password_token(tl::enums::PasswordKdfAlgo::Unknown);
+        assert!(new_password_settings(&token, &NewPassword::new("secret")).is_err());
+    }
+
+    #[test]
+    fn new_password_with_unsafe_parameters() {
+        let token = password_token(
+            tl::types::PasswordKdfAlgoSha256Sha256Pbkdf2Hmacsha512iter100000Sha256ModPow {
+                salt1: vec![1; 8],
+                salt2: vec![2; 16],
+                g: 3,
+                p: vec![0xff; 256],
+            }
+            .into(),
+        );
+        assert!(new_password_settings(&token, &NewPassword::new("secret")).is_err());
+    }
+}
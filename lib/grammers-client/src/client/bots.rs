@@ -7,6 +7,7 @@
 // except according to those terms.
 use crate::Client;
 use crate::client::messages::parse_mention_entities;
+use crate::types::{BotAdminRights, BotCommand, BotCommandScope, BotInfo, BotMenuButton};
 use crate::utils::generate_random_id;
 use crate::{InputMessage, types::IterBuffer};
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
@@ -200,4 +201,268 @@ impl Client {
             .await
         }
     }
+
+    /// Set the list of commands of the bot for the given scope and language.
+    ///
+    /// An empty language code applies to all users for whose language there are no dedicated
+    /// commands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::{BotCommand, BotCommandScope};
+    ///
+    /// client
+    ///     .set_bot_commands(
+    ///         BotCommandScope::Default,
+    ///         "",
+    ///         &[
+    ///             BotCommand::new("start", "Start using the bot"),
+    ///             BotCommand::new("help", "Show how to use the bot"),
+    ///         ],
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_bot_commands(
+        &self,
+        scope: BotCommandScope,
+        lang_code: &str,
+        commands: &[BotCommand],
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::bots::SetBotCommands {
+            scope: scope.to_raw(),
+            lang_code: lang_code.to_string(),
+            commands: commands.iter().map(BotCommand::to_raw).collect(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Get the list of commands of the bot for the given scope and language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::BotCommandScope;
+    ///
+    /// for command in client.get_bot_commands(BotCommandScope::AllGroupChats, "en").await? {
+    ///     println!("/{} - {}", command.command, command.description);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_bot_commands(
+        &self,
+        scope: BotCommandScope,
+        lang_code: &str,
+    ) -> Result<Vec<BotCommand>, InvocationError> {
+        self.invoke(&tl::functions::bots::GetBotCommands {
+            scope: scope.to_raw(),
+            lang_code: lang_code.to_string(),
+        })
+        .await
+        .map(|commands| commands.into_iter().map(BotCommand::from_raw).collect())
+    }
+
+    /// Remove the list of commands of the bot for the given scope and language, so that the
+    /// commands of a broader scope or language are used instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::BotCommandScope;
+    ///
+    /// client.reset_bot_commands(BotCommandScope::AllPrivateChats, "").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reset_bot_commands(
+        &self,
+        scope: BotCommandScope,
+        lang_code: &str,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::bots::ResetBotCommands {
+            scope: scope.to_raw(),
+            lang_code: lang_code.to_string(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Get the name and descriptions of the bot in the given language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let info = client.get_bot_info("").await?;
+    /// println!("{:?}: {:?}", info.name, info.about);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_bot_info(&self, lang_code: &str) -> Result<BotInfo, InvocationError> {
+        self.invoke(&tl::functions::bots::GetBotInfo {
+            bot: None,
+            lang_code: lang_code.to_string(),
+        })
+        .await
+        .map(BotInfo::from_raw)
+    }
+
+    /// Set the name and descriptions of the bot in the given language.
+    ///
+    /// Only the fields which are `Some` are changed, so the rest keep their current value.
+    ///
+    /// An empty language code applies to all users for whose language there is no dedicated
+    /// information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::BotInfo;
+    ///
+    /// client
+    ///     .set_bot_info(
+    ///         "",
+    ///         &BotInfo {
+    ///             about: Some("Repeats what you say".to_string()),
+    ///             description: Some("Send any message and I will send it back.".to_string()),
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_bot_info(
+        &self,
+        lang_code: &str,
+        info: &BotInfo,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::bots::SetBotInfo {
+            bot: None,
+            lang_code: lang_code.to_string(),
+            name: info.name.clone(),
+            about: info.about.clone(),
+            description: info.description.clone(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Get the menu button of the bot shown to the given user, or the one shown to all users
+    /// by default if `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let button = client
+    ///     .get_bot_menu_button(None::<grammers_client::types::PackedChat>)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_bot_menu_button<C: Into<PackedChat>>(
+        &self,
+        user: Option<C>,
+    ) -> Result<BotMenuButton, InvocationError> {
+        self.invoke(&tl::functions::bots::GetBotMenuButton {
+            user_id: user
+                .map(|u| u.into().to_input_user_lossy())
+                .unwrap_or(tl::enums::InputUser::Empty),
+        })
+        .await
+        .map(BotMenuButton::from_raw)
+    }
+
+    /// Set the menu button of the bot shown to the given user, or the one shown to all users
+    /// by default if `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::{BotMenuButton, PackedChat};
+    ///
+    /// client
+    ///     .set_bot_menu_button(
+    ///         None::<PackedChat>,
+    ///         BotMenuButton::WebApp {
+    ///             text: "Open".to_string(),
+    ///             url: "https://example.com".to_string(),
+    ///         },
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_bot_menu_button<C: Into<PackedChat>>(
+        &self,
+        user: Option<C>,
+        button: BotMenuButton,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::bots::SetBotMenuButton {
+            user_id: user
+                .map(|u| u.into().to_input_user_lossy())
+                .unwrap_or(tl::enums::InputUser::Empty),
+            button: button.to_raw(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Set the administrator rights the bot suggests when it's added to groups.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::BotAdminRights;
+    ///
+    /// let rights = BotAdminRights::new().delete_messages(true).ban_users(true);
+    /// client.set_bot_group_default_admin_rights(rights).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_bot_group_default_admin_rights(
+        &self,
+        rights: BotAdminRights,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::bots::SetBotGroupDefaultAdminRights {
+            admin_rights: rights.raw.into(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Set the administrator rights the bot suggests when it's added to channels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::BotAdminRights;
+    ///
+    /// let rights = BotAdminRights::new().post_messages(true).edit_messages(true);
+    /// client.set_bot_channel_default_admin_rights(rights).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_bot_channel_default_admin_rights(
+        &self,
+        rights: BotAdminRights,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::bots::SetBotBroadcastDefaultAdminRights {
+            admin_rights: rights.raw.into(),
+        })
+        .await
+        .map(drop)
+    }
 }
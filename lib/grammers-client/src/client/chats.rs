@@ -10,9 +10,11 @@
 
 use super::Client;
 use crate::types::{
-    AdminRightsBuilder, BannedRightsBuilder, Chat, ChatMap, IterBuffer, Message, Participant,
-    Photo, User, chats::AdminRightsBuilderInner, chats::BannedRightsBuilderInner,
+    AdminRightsBuilder, BannedRightsBuilder, ChannelKind, Chat, ChatMap, IterBuffer, Message,
+    Participant, Photo, Uploaded, User, chats::AdminRightsBuilderInner,
+    chats::BannedRightsBuilderInner,
 };
+use crate::utils::bad_request;
 use grammers_mtsender::RpcError;
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
 use grammers_session::{PackedChat, PackedType};
@@ -555,11 +557,43 @@ impl Client {
         BannedRightsBuilder::new(
             self.clone(),
             channel.into(),
-            user.into(),
+            Some(user.into()),
             BannedRightsBuilderInner::invoke,
         )
     }
 
+    /// Set the default rights of the participants in a group or channel, applied to everyone
+    /// who is not an administrator and has no individual restrictions.
+    ///
+    /// Returns a new [`BannedRightsBuilder`] instance. Like [`Client::set_banned_rights`],
+    /// nothing is done until it is awaited, and all rights are granted unless taken away.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Nobody other than the administrators can send polls
+    /// client
+    ///     .set_default_banned_rights(&chat)
+    ///     .load_current()
+    ///     .await?
+    ///     .send_polls(false)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_default_banned_rights<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+    ) -> BannedRightsBuilder<impl Future<Output = Result<(), InvocationError>>> {
+        BannedRightsBuilder::new(
+            self.clone(),
+            chat.into(),
+            None,
+            BannedRightsBuilderInner::invoke_default,
+        )
+    }
+
     /// Set the administrator rights for a specific user.
     ///
     /// Returns a new [`AdminRightsBuilder`] instance. Check out the documentation for that
@@ -855,6 +889,337 @@ impl Client {
         ))
     }
 
+    /// Create a new small group chat with the given title and users.
+    ///
+    /// At least one other user must be invited for the group to be created. Users whose privacy
+    /// settings do not allow them to be invited will be missing from the group.
+    ///
+    /// Returns the new group, if Telegram included it in the response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(friend: grammers_client::types::User, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(group) = client.create_group("Weekend plans", &[&friend]).await? {
+    ///     println!("Created {}", group.id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_group<C: Into<PackedChat> + Copy>(
+        &self,
+        title: &str,
+        users: &[C],
+    ) -> Result<Option<Chat>, InvocationError> {
+        let tl::enums::messages::InvitedUsers::Users(invited) = self
+            .invoke(&tl::functions::messages::CreateChat {
+                users: users
+                    .iter()
+                    .map(|user| (*user).into().to_input_user_lossy())
+                    .collect(),
+                title: title.to_string(),
+                ttl_period: None,
+            })
+            .await?;
+        Ok(updates_to_chat(None, invited.updates))
+    }
+
+    /// Create a new broadcast channel, supergroup or forum.
+    ///
+    /// Returns the new channel, if Telegram included it in the response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::ChannelKind;
+    ///
+    /// let channel = client
+    ///     .create_channel("News", "Daily updates", ChannelKind::Broadcast)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_channel(
+        &self,
+        title: &str,
+        about: &str,
+        kind: ChannelKind,
+    ) -> Result<Option<Chat>, InvocationError> {
+        let updates = self
+            .invoke(&tl::functions::channels::CreateChannel {
+                broadcast: kind == ChannelKind::Broadcast,
+                megagroup: kind != ChannelKind::Broadcast,
+                for_import: false,
+                forum: kind == ChannelKind::Forum,
+                title: title.to_string(),
+                about: about.to_string(),
+                geo_point: None,
+                address: None,
+                ttl_period: None,
+            })
+            .await?;
+        Ok(updates_to_chat(None, updates))
+    }
+
+    /// Change the title of a group or channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.edit_title(&chat, "New title").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn edit_title<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        title: &str,
+    ) -> Result<(), InvocationError> {
+        let chat: PackedChat = chat.into();
+        if let Some(channel) = chat.try_to_input_channel() {
+            self.invoke(&tl::functions::channels::EditTitle {
+                channel,
+                title: title.to_string(),
+            })
+            .await
+            .map(drop)
+        } else if let Some(chat_id) = chat.try_to_chat_id() {
+            self.invoke(&tl::functions::messages::EditChatTitle {
+                chat_id,
+                title: title.to_string(),
+            })
+            .await
+            .map(drop)
+        } else {
+            Err(bad_request("PEER_ID_INVALID"))
+        }
+    }
+
+    /// Change the description of a group or channel. Pass an empty string to remove it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.edit_about(&chat, "Be nice to each other").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn edit_about<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        about: &str,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::EditChatAbout {
+            peer: chat.into().to_input_peer(),
+            about: about.to_string(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Change the photo of a group or channel to a previously-uploaded photo, or remove it with
+    /// `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let uploaded = client.upload_file("logo.jpg").await?;
+    /// client.edit_photo(&chat, Some(uploaded)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn edit_photo<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        photo: Option<Uploaded>,
+    ) -> Result<(), InvocationError> {
+        let chat: PackedChat = chat.into();
+        let photo = match photo {
+            Some(photo) => tl::types::InputChatUploadedPhoto {
+                file: Some(photo.raw),
+                video: None,
+                video_start_ts: None,
+                video_emoji_markup: None,
+            }
+            .into(),
+            None => tl::enums::InputChatPhoto::Empty,
+        };
+        if let Some(channel) = chat.try_to_input_channel() {
+            self.invoke(&tl::functions::channels::EditPhoto { channel, photo })
+                .await
+                .map(drop)
+        } else if let Some(chat_id) = chat.try_to_chat_id() {
+            self.invoke(&tl::functions::messages::EditChatPhoto { chat_id, photo })
+                .await
+                .map(drop)
+        } else {
+            Err(bad_request("PEER_ID_INVALID"))
+        }
+    }
+
+    /// Set how long participants of a supergroup must wait between sending messages.
+    ///
+    /// Telegram only accepts a few specific durations (such as 10 seconds, 1 minute or 1 hour).
+    /// Use a zero duration to disable the slow mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    ///
+    /// client.set_slow_mode(&chat, Duration::from_secs(30)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_slow_mode<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        interval: Duration,
+    ) -> Result<(), InvocationError> {
+        let channel = chat
+            .into()
+            .try_to_input_channel()
+            .ok_or_else(|| bad_request("PEER_ID_INVALID"))?;
+        self.invoke(&tl::functions::channels::ToggleSlowMode {
+            channel,
+            seconds: interval.as_secs().try_into().unwrap_or(i32::MAX),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Enable or disable signing the messages posted in a broadcast channel with the name of
+    /// the administrator who posted them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(channel: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.toggle_signatures(&channel, true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn toggle_signatures<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        enabled: bool,
+    ) -> Result<(), InvocationError> {
+        let channel = chat
+            .into()
+            .try_to_input_channel()
+            .ok_or_else(|| bad_request("PEER_ID_INVALID"))?;
+        self.invoke(&tl::functions::channels::ToggleSignatures {
+            signatures_enabled: enabled,
+            profiles_enabled: false,
+            channel,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Convert a small group chat into a supergroup.
+    ///
+    /// Only the creator of the group can do this. The old group is deactivated and the new
+    /// supergroup is returned, if Telegram included it in the response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(group: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(supergroup) = client.migrate_to_supergroup(&group).await? {
+    ///     println!("The group now lives at {}", supergroup.id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn migrate_to_supergroup<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+    ) -> Result<Option<Chat>, InvocationError> {
+        let chat_id = chat
+            .into()
+            .try_to_chat_id()
+            .ok_or_else(|| bad_request("PEER_ID_INVALID"))?;
+        let chats = match self
+            .invoke(&tl::functions::messages::MigrateChat { chat_id })
+            .await?
+        {
+            tl::enums::Updates::Combined(updates) => updates.chats,
+            tl::enums::Updates::Updates(updates) => updates.chats,
+            _ => Vec::new(),
+        };
+        Ok(chats
+            .into_iter()
+            .find(|chat| matches!(chat, tl::enums::Chat::Channel(_)))
+            .map(Chat::from_raw))
+    }
+
+    /// Delete a group or channel for everyone.
+    ///
+    /// Only the creator can do this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.delete_channel(&chat).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_channel<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+    ) -> Result<(), InvocationError> {
+        let chat: PackedChat = chat.into();
+        if let Some(channel) = chat.try_to_input_channel() {
+            self.invoke(&tl::functions::channels::DeleteChannel { channel })
+                .await
+                .map(drop)
+        } else if let Some(chat_id) = chat.try_to_chat_id() {
+            self.invoke(&tl::functions::messages::DeleteChat { chat_id })
+                .await
+                .map(drop)
+        } else {
+            Err(bad_request("PEER_ID_INVALID"))
+        }
+    }
+
+    /// Leave a group or channel.
+    ///
+    /// The chat is not deleted, and the history is kept in small group chats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.leave_chat(&chat).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn leave_chat<C: Into<PackedChat>>(&self, chat: C) -> Result<(), InvocationError> {
+        let chat: PackedChat = chat.into();
+        if let Some(channel) = chat.try_to_input_channel() {
+            self.invoke(&tl::functions::channels::LeaveChannel { channel })
+                .await
+                .map(drop)
+        } else if let Some(chat_id) = chat.try_to_chat_id() {
+            self.invoke(&tl::functions::messages::DeleteChatUser {
+                revoke_history: false,
+                chat_id,
+                user_id: tl::enums::InputUser::UserSelf,
+            })
+            .await
+            .map(drop)
+        } else {
+            Err(bad_request("PEER_ID_INVALID"))
+        }
+    }
+
     /// Send a message action (such as typing, uploading photo, or viewing an emoji interaction)
     ///
     /// # Examples
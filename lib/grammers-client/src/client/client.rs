@@ -14,10 +14,12 @@ use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::sync::atomic::AtomicU32;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
 use web_time::Instant;
 
 use super::net;
+use super::rate_limit::{RateLimit, RateLimiter};
 
 /// When no locale is found, use this one instead.
 const DEFAULT_LOCALE: &str = "en";
@@ -75,6 +77,31 @@ pub struct InitParams {
     /// On flood, the library will retry *once*. If the flood error occurs a second time after
     /// sleeping, the error will be returned.
     pub flood_sleep_threshold: u32,
+    /// How long should requests be allowed to take before they are cancelled and fail with
+    /// [`InvocationError::Timeout`]. Individual requests can override this with
+    /// [`Client::invoke_with_timeout`].
+    ///
+    /// The timeout includes any time spent sleeping on flood waits (see
+    /// [`InitParams::flood_sleep_threshold`]).
+    ///
+    /// By default, there is no timeout, and requests will wait for as long as it takes to get
+    /// a response.
+    ///
+    /// [`InvocationError::Timeout`]: grammers_mtsender::InvocationError::Timeout
+    pub request_timeout: Option<Duration>,
+    /// How many requests may be pending on a connection at any given time, including both the
+    /// requests waiting to be sent and those waiting for a response.
+    ///
+    /// Upon reaching this limit, invoking more requests will wait until some of the pending
+    /// requests complete. This prevents bulk operations from piling up an ever-growing queue.
+    /// Requests with [`Priority::High`] are not subject to this limit.
+    ///
+    /// A limit of `None` or zero (`0`) disables the upper bound.
+    ///
+    /// By default, there is no limit.
+    ///
+    /// [`Priority::High`]: grammers_mtsender::Priority::High
+    pub request_queue_limit: Option<usize>,
     /// How many updates may be buffered by the client at any given time.
     ///
     /// Telegram passively sends updates to the client through the open connection, so they must
@@ -95,6 +122,14 @@ pub struct InitParams {
     ///
     /// When the limit is `Some`, a buffer to hold that many updates will be pre-allocated.
     pub update_queue_limit: Option<usize>,
+    /// How many requests the client may invoke over a period of time.
+    ///
+    /// Requests exceeding the limit are delayed until they can be made without going over it.
+    /// Requests the library makes on its own, such as those needed to fetch missed updates, are
+    /// not limited.
+    ///
+    /// By default, there is no limit, and Telegram's flood errors are the only restriction.
+    pub rate_limit: Option<RateLimit>,
     /// URL of the proxy to use. Requires the `proxy` feature to be enabled.
     ///
     /// The scheme must be `socks5`. Username and password are optional.
@@ -105,6 +140,16 @@ pub struct InitParams {
     #[cfg(feature = "proxy")]
     pub proxy_url: Option<String>,
 
+    /// Whether the connection to Telegram should use the HTTP transport instead of plain TCP.
+    ///
+    /// This is only useful in networks where nothing but plain HTTP requests can be made, as the
+    /// HTTP transport has a much larger overhead. The client will keep a long poll request open
+    /// so that updates can still be received as soon as they occur.
+    ///
+    /// By default, HTTP is not used.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub use_http: bool,
+
     /// specify the reconnection policy which will be used by client to determine whether to re-connect on failure or not.
     ///
     ///it can be one of the 2 default implementation [`NoReconnect`] and [`FixedReconnect`];
@@ -127,6 +172,9 @@ pub(crate) struct ClientInner {
     pub(crate) state: RwLock<ClientState>,
     // Stores per-datacenter downloader instances
     pub(crate) downloader_map: AsyncRwLock<HashMap<i32, Arc<Connection>>>,
+    // Datacenter addresses, possibly shared with other clients in a `ClientPool`.
+    pub(crate) dc_options: Arc<net::DcOptions>,
+    pub(crate) rate_limiter: Option<RateLimiter>,
 }
 
 pub(crate) struct ClientState {
@@ -137,11 +185,14 @@ pub(crate) struct ClientState {
     // This is used to avoid spamming the log.
     pub(crate) last_update_limit_warn: Option<Instant>,
     pub(crate) updates: VecDeque<(tl::enums::Update, Arc<crate::types::ChatMap>)>,
+    // Set when `updateLoginToken` arrives, meaning the QR login token was accepted.
+    pub(crate) login_token_updated: bool,
 }
 
 pub(crate) struct Connection {
     pub(crate) sender: AsyncMutex<Sender<net::Transport, mtp::Encrypted>>,
     pub(crate) request_tx: RwLock<Enqueuer>,
+    pub(crate) stats: RwLock<sender::Stats>,
     pub(crate) step_counter: AtomicU32,
 }
 
@@ -187,9 +238,14 @@ impl Default for InitParams {
             catch_up: false,
             server_addr: None,
             flood_sleep_threshold: 60,
+            request_timeout: None,
+            request_queue_limit: None,
             update_queue_limit: Some(100),
+            rate_limit: None,
             #[cfg(feature = "proxy")]
             proxy_url: None,
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            use_http: false,
             reconnection_policy: &grammers_mtsender::NoReconnect,
         }
     }
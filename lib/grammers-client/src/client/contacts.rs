@@ -0,0 +1,335 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Methods related to contacts and blocked users.
+
+use super::Client;
+use crate::types::{Chat, ChatMap, ImportedContacts, InputContact, IterBuffer, User};
+pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+
+const MAX_BLOCKED_LIMIT: usize = 100;
+
+pub type ContactIter = IterBuffer<tl::functions::contacts::GetContacts, User>;
+
+impl ContactIter {
+    fn new(client: &Client) -> Self {
+        Self::from_request(client, 0, tl::functions::contacts::GetContacts { hash: 0 })
+    }
+
+    /// Determines how many contacts there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.fill_buffer().await?;
+        Ok(self.total.unwrap())
+    }
+
+    /// Fetch all the contacts at once, since the request is not paginated.
+    async fn fill_buffer(&mut self) -> Result<(), InvocationError> {
+        let contacts = match self.client.invoke(&self.request).await? {
+            tl::enums::contacts::Contacts::Contacts(contacts) => contacts,
+            tl::enums::contacts::Contacts::NotModified => {
+                panic!("API returned Contacts::NotModified even though hash = 0")
+            }
+        };
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&contacts.users, &[]);
+        }
+
+        self.last_chunk = true;
+        self.total = Some(contacts.users.len());
+        self.buffer
+            .extend(contacts.users.into_iter().map(User::from_raw));
+        Ok(())
+    }
+
+    /// Return the next `User` from the internal buffer, filling the buffer previously if it's
+    /// empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no contacts left.
+    pub async fn next(&mut self) -> Result<Option<User>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.fill_buffer().await?;
+        Ok(self.pop_item())
+    }
+}
+
+pub type BlockedIter = IterBuffer<tl::functions::contacts::GetBlocked, Chat>;
+
+impl BlockedIter {
+    fn new(client: &Client) -> Self {
+        Self::from_request(
+            client,
+            MAX_BLOCKED_LIMIT,
+            tl::functions::contacts::GetBlocked {
+                my_stories_from: false,
+                offset: 0,
+                limit: 0,
+            },
+        )
+    }
+
+    /// Determines how many blocked users and chats there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        use tl::enums::contacts::Blocked;
+
+        self.request.limit = 1;
+        let total = match self.client.invoke(&self.request).await? {
+            Blocked::Blocked(blocked) => blocked.blocked.len(),
+            Blocked::Slice(blocked) => blocked.count as usize,
+        };
+        self.total = Some(total);
+        Ok(total)
+    }
+
+    /// Return the next blocked `Chat` from the internal buffer, filling the buffer previously if
+    /// it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no blocked chats left.
+    pub async fn next(&mut self) -> Result<Option<Chat>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        use tl::enums::contacts::Blocked;
+
+        self.request.limit = self.determine_limit(MAX_BLOCKED_LIMIT);
+        let (blocked, users, chats) = match self.client.invoke(&self.request).await? {
+            Blocked::Blocked(b) => {
+                self.last_chunk = true;
+                self.total = Some(b.blocked.len());
+                (b.blocked, b.users, b.chats)
+            }
+            Blocked::Slice(b) => {
+                self.last_chunk = b.blocked.len() < self.request.limit as usize;
+                self.total = Some(b.count as usize);
+                (b.blocked, b.users, b.chats)
+            }
+        };
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&users, &chats);
+        }
+
+        self.request.offset += blocked.len() as i32;
+        let chats = ChatMap::new(users, chats);
+        self.buffer.extend(
+            blocked
+                .into_iter()
+                .filter_map(|tl::enums::PeerBlocked::Blocked(b)| chats.get(&b.peer_id).cloned()),
+        );
+
+        Ok(self.pop_item())
+    }
+}
+
+impl Client {
+    /// Import contacts by their phone number, adding them to the contacts of the account.
+    ///
+    /// Only the contacts whose phone number belongs to a Telegram account are imported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::InputContact;
+    ///
+    /// let contacts = [
+    ///     InputContact::new("+34600000000", "Jane", "Doe"),
+    ///     InputContact::new("+34600000001", "John", ""),
+    /// ];
+    /// let result = client.import_contacts(&contacts).await?;
+    /// for (i, user) in result.imported {
+    ///     println!("{} is {}", contacts[i].phone, user.id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_contacts(
+        &self,
+        contacts: &[InputContact],
+    ) -> Result<ImportedContacts, InvocationError> {
+        let tl::enums::contacts::ImportedContacts::Contacts(imported) = self
+            .invoke(&tl::functions::contacts::ImportContacts {
+                contacts: contacts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, contact)| contact.to_raw(i as i64))
+                    .collect(),
+            })
+            .await?;
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&imported.users, &[]);
+        }
+
+        Ok(ImportedContacts::from_raw(imported))
+    }
+
+    /// Iterate over the contacts of the account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut contacts = client.iter_contacts();
+    ///
+    /// while let Some(user) = contacts.next().await? {
+    ///     println!("{}", user.full_name());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_contacts(&self) -> ContactIter {
+        ContactIter::new(self)
+    }
+
+    /// Remove the given users from the contacts of the account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(user: grammers_client::types::User, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.delete_contacts([&user]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_contacts<C: Into<PackedChat>, I: IntoIterator<Item = C>>(
+        &self,
+        users: I,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::contacts::DeleteContacts {
+            id: users
+                .into_iter()
+                .map(|user| user.into().to_input_user_lossy())
+                .collect(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Block a user (or chat), preventing them from contacting the account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(user: grammers_client::types::User, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.block(&user).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn block<C: Into<PackedChat>>(&self, chat: C) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::contacts::Block {
+            my_stories_from: false,
+            id: chat.into().to_input_peer(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Unblock a previously-[blocked](Client::block) user (or chat).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(user: grammers_client::types::User, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.unblock(&user).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn unblock<C: Into<PackedChat>>(&self, chat: C) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::contacts::Unblock {
+            my_stories_from: false,
+            id: chat.into().to_input_peer(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Iterate over the users (and chats) blocked by the account, most recently blocked first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut blocked = client.iter_blocked();
+    ///
+    /// while let Some(chat) = blocked.next().await? {
+    ///     println!("{} is blocked", chat.id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_blocked(&self) -> BlockedIter {
+        BlockedIter::new(self)
+    }
+
+    /// Find the user with the given phone number.
+    ///
+    /// Returns `None` if the phone number does not belong to a Telegram account, or if the user
+    /// does not allow being found by their phone number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(user) = client.resolve_phone("+34600000000").await? {
+    ///     println!("Found {}", user.full_name());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_phone(&self, phone: &str) -> Result<Option<User>, InvocationError> {
+        let tl::types::contacts::ResolvedPeer { peer, users, chats } = match self
+            .invoke(&tl::functions::contacts::ResolvePhone {
+                phone: phone.to_string(),
+            })
+            .await
+        {
+            Ok(tl::enums::contacts::ResolvedPeer::Peer(p)) => p,
+            Err(err) if err.is("PHONE_NOT_OCCUPIED") => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&users, &chats);
+        }
+
+        Ok(match peer {
+            tl::enums::Peer::User(tl::types::PeerUser { user_id }) => users
+                .into_iter()
+                .map(User::from_raw)
+                .find(|user| user.id() == user_id),
+            _ => None,
+        })
+    }
+}
@@ -5,8 +5,10 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
-use crate::Client;
-use crate::types::{ChatMap, Dialog, IterBuffer, Message};
+use crate::client::messages::parse_mention_entities;
+use crate::types::reply_to::input_reply_to;
+use crate::types::{ChatMap, Dialog, Draft, IterBuffer, Message};
+use crate::{Client, InputMessage, utils};
 use grammers_mtsender::InvocationError;
 use grammers_session::PackedChat;
 use grammers_tl_types as tl;
@@ -257,4 +259,138 @@ impl Client {
         .await
         .map(drop)
     }
+
+    /// Marks all the reactions to the messages of the logged-in account in a chat as seen.
+    ///
+    /// If a `topic_id` is given, only the reactions within that forum topic are marked as seen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Mark the reactions in the whole chat as seen.
+    /// client.read_reactions(&chat, None).await?;
+    ///
+    /// // Or only those in a forum topic.
+    /// let topic_id = 123;
+    /// client.read_reactions(&chat, Some(topic_id)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_reactions<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        topic_id: Option<i32>,
+    ) -> Result<(), InvocationError> {
+        let peer = chat.into().to_input_peer();
+        loop {
+            // Telegram marks the reactions in batches, and must be asked again until done.
+            let tl::enums::messages::AffectedHistory::History(affected) = self
+                .invoke(&tl::functions::messages::ReadReactions {
+                    peer: peer.clone(),
+                    top_msg_id: topic_id,
+                })
+                .await?;
+            if affected.offset <= 0 {
+                break Ok(());
+            }
+        }
+    }
+
+    /// Save a message as the draft of a chat, without sending it.
+    ///
+    /// The text, formatting entities, media, link preview and reply of the message are saved.
+    /// Saving an empty message clears the draft.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.save_draft(&chat, "I'll finish this later").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn save_draft<C: Into<PackedChat>, M: Into<InputMessage>>(
+        &self,
+        chat: C,
+        message: M,
+    ) -> Result<(), InvocationError> {
+        let message = message.into();
+        self.invoke(&tl::functions::messages::SaveDraft {
+            no_webpage: !message.link_preview,
+            invert_media: message.invert_media,
+            reply_to: input_reply_to(message.reply_to.as_ref(), message.topic_id),
+            peer: chat.into().to_input_peer(),
+            message: message.text,
+            entities: parse_mention_entities(self, message.entities),
+            media: message.media,
+            effect: message.effect,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Get the drafts of all the chats of the account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// for draft in client.get_drafts().await? {
+    ///     println!("Draft in {}: {}", draft.chat().id(), draft.text());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_drafts(&self) -> Result<Vec<Draft>, InvocationError> {
+        let (updates, users, chats) = match self
+            .invoke(&tl::functions::messages::GetAllDrafts {})
+            .await?
+        {
+            tl::enums::Updates::Updates(updates) => (updates.updates, updates.users, updates.chats),
+            tl::enums::Updates::Combined(updates) => {
+                (updates.updates, updates.users, updates.chats)
+            }
+            _ => return Ok(Vec::new()),
+        };
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&users, &chats);
+        }
+
+        let chats = ChatMap::new(users, chats);
+        Ok(updates
+            .into_iter()
+            .filter_map(|update| match update {
+                tl::enums::Update::DraftMessage(update) => Some(update),
+                _ => None,
+            })
+            .filter(|update| matches!(update.draft, tl::enums::DraftMessage::Message(_)))
+            .map(|update| {
+                Draft::new(
+                    update.draft,
+                    utils::always_find_entity(&update.peer, &chats, self),
+                    update.top_msg_id,
+                )
+            })
+            .collect())
+    }
+
+    /// Clear the drafts of all the chats of the account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// client.clear_all_drafts().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn clear_all_drafts(&self) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::ClearAllDrafts {})
+            .await
+            .map(drop)
+    }
 }
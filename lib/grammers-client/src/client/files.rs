@@ -10,7 +10,7 @@ use crate::Client;
 use crate::types::{Downloadable, Uploaded};
 use crate::utils::generate_random_id;
 use futures_util::stream::{FuturesUnordered, StreamExt as _};
-use grammers_mtsender::InvocationError;
+use grammers_mtsender::{InvocationError, Priority};
 use grammers_tl_types as tl;
 use std::mem;
 use std::sync::Arc;
@@ -104,7 +104,11 @@ impl DownloadIter {
         let mut dc: Option<u32> = None;
         loop {
             let result = match dc.take() {
-                None => self.client.invoke(&request).await,
+                None => {
+                    self.client
+                        .invoke_with_priority(&request, Priority::Low)
+                        .await
+                }
                 Some(dc) => self.client.invoke_in_dc(&request, dc as i32).await,
             };
 
@@ -286,7 +290,7 @@ impl Client {
                         limit: MAX_CHUNK_SIZE,
                     };
                     let res = match dc {
-                        None => client.invoke(request).await,
+                        None => client.invoke_with_priority(request, Priority::Low).await,
                         Some(dc) => client.invoke_in_dc(request, dc as i32).await,
                     };
                     match res {
@@ -397,12 +401,15 @@ impl Client {
                 let task = async move {
                     while let Some((part, bytes)) = parts.next_part().await? {
                         let ok = handle
-                            .invoke(&tl::functions::upload::SaveBigFilePart {
-                                file_id,
-                                file_part: part,
-                                file_total_parts: total_parts,
-                                bytes,
-                            })
+                            .invoke_with_priority(
+                                &tl::functions::upload::SaveBigFilePart {
+                                    file_id,
+                                    file_part: part,
+                                    file_total_parts: total_parts,
+                                    bytes,
+                                },
+                                Priority::Low,
+                            )
                             .await
                             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
@@ -435,11 +442,14 @@ impl Client {
             while let Some((part, bytes)) = parts.next_part().await? {
                 md5.consume(&bytes);
                 let ok = self
-                    .invoke(&tl::functions::upload::SaveFilePart {
-                        file_id,
-                        file_part: part,
-                        bytes,
-                    })
+                    .invoke_with_priority(
+                        &tl::functions::upload::SaveFilePart {
+                            file_id,
+                            file_part: part,
+                            bytes,
+                        },
+                        Priority::Low,
+                    )
                     .await
                     .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
@@ -0,0 +1,260 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Methods related to the topics of forum supergroups.
+
+use super::Client;
+use super::messages::map_random_ids_to_messages;
+use crate::types::{EditForumTopic, ForumTopic, IterBuffer, Message};
+use crate::utils::{bad_request, generate_random_id};
+pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+
+const MAX_TOPIC_LIMIT: usize = 100;
+
+pub type ForumTopicIter = IterBuffer<tl::functions::channels::GetForumTopics, ForumTopic>;
+
+impl ForumTopicIter {
+    fn new(client: &Client, channel: tl::enums::InputChannel) -> Self {
+        Self::from_request(
+            client,
+            MAX_TOPIC_LIMIT,
+            tl::functions::channels::GetForumTopics {
+                channel,
+                q: None,
+                offset_date: 0,
+                offset_id: 0,
+                offset_topic: 0,
+                limit: 0,
+            },
+        )
+    }
+
+    /// Only return the topics whose title matches the given query.
+    pub fn query(mut self, query: &str) -> Self {
+        self.request.q = Some(query.to_string());
+        self
+    }
+
+    /// Determines how many topics there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.request.limit = 1;
+        let tl::enums::messages::ForumTopics::Topics(topics) =
+            self.client.invoke(&self.request).await?;
+        let total = topics.count as usize;
+        self.total = Some(total);
+        Ok(total)
+    }
+
+    /// Return the next `ForumTopic` from the internal buffer, filling the buffer previously if
+    /// it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no topics left.
+    pub async fn next(&mut self) -> Result<Option<ForumTopic>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_TOPIC_LIMIT);
+        let tl::enums::messages::ForumTopics::Topics(topics) =
+            self.client.invoke(&self.request).await?;
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&topics.users, &topics.chats);
+        }
+
+        let count = topics.topics.len();
+        self.total = Some(topics.count as usize);
+        if count < self.request.limit as usize {
+            self.last_chunk = true;
+        }
+
+        // The offset is made from the last topic, even if it was deleted, along with the date
+        // of its last message.
+        if let Some(tl::enums::ForumTopic::Topic(last)) = topics.topics.last() {
+            self.request.offset_topic = last.id;
+            self.request.offset_id = last.top_message;
+            self.request.offset_date = topics
+                .messages
+                .iter()
+                .find(|message| message.id() == last.top_message)
+                .and_then(|message| match message {
+                    tl::enums::Message::Message(message) => Some(message.date),
+                    tl::enums::Message::Service(message) => Some(message.date),
+                    tl::enums::Message::Empty(_) => None,
+                })
+                .unwrap_or(last.date);
+        } else if let Some(tl::enums::ForumTopic::Deleted(last)) = topics.topics.last() {
+            self.request.offset_topic = last.id;
+        }
+
+        self.buffer
+            .extend(topics.topics.into_iter().filter_map(ForumTopic::from_raw));
+
+        Ok(self.pop_item())
+    }
+}
+
+impl Client {
+    /// Iterate over the topics of a forum supergroup, most recently active first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut topics = client.iter_forum_topics(&chat);
+    ///
+    /// while let Some(topic) = topics.next().await? {
+    ///     println!("{} (id {})", topic.title(), topic.id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_forum_topics<C: Into<PackedChat>>(&self, chat: C) -> ForumTopicIter {
+        let channel = chat
+            .into()
+            .try_to_input_channel()
+            .unwrap_or(tl::enums::InputChannel::Empty);
+        ForumTopicIter::new(self, channel)
+    }
+
+    /// Create a new topic in a forum supergroup.
+    ///
+    /// The icon of the topic can be a custom emoji. Otherwise, Telegram picks a color for the
+    /// default icon.
+    ///
+    /// Returns the service message that created the topic, whose identifier is also the
+    /// identifier of the new topic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::InputMessage;
+    ///
+    /// if let Some(created) = client.create_forum_topic(&chat, "Announcements", None).await? {
+    ///     client
+    ///         .send_message(&chat, InputMessage::text("Welcome!").topic(created.id()))
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_forum_topic<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        title: &str,
+        icon_emoji_id: Option<i64>,
+    ) -> Result<Option<Message>, InvocationError> {
+        let channel = chat
+            .into()
+            .try_to_input_channel()
+            .ok_or_else(|| bad_request("CHANNEL_INVALID"))?;
+        let random_id = generate_random_id();
+        let updates = self
+            .invoke(&tl::functions::channels::CreateForumTopic {
+                channel,
+                title: title.to_string(),
+                icon_color: None,
+                icon_emoji_id,
+                random_id,
+                send_as: None,
+            })
+            .await?;
+        Ok(map_random_ids_to_messages(self, &[random_id], updates)
+            .pop()
+            .flatten())
+    }
+
+    /// Edit a topic of a forum supergroup.
+    ///
+    /// Only the settings present in the [`EditForumTopic`] are changed. The "General" topic (with
+    /// ID `1`) cannot be given an icon, but it can be hidden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::EditForumTopic;
+    ///
+    /// let topic_id = 123;
+    ///
+    /// // Rename the topic and close it, so that only administrators can post into it.
+    /// client
+    ///     .edit_forum_topic(&chat, topic_id, EditForumTopic::new().title("Archive").closed(true))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn edit_forum_topic<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        topic_id: i32,
+        edit: EditForumTopic,
+    ) -> Result<(), InvocationError> {
+        let channel = chat
+            .into()
+            .try_to_input_channel()
+            .ok_or_else(|| bad_request("CHANNEL_INVALID"))?;
+        self.invoke(&tl::functions::channels::EditForumTopic {
+            channel,
+            topic_id,
+            title: edit.title,
+            icon_emoji_id: edit.icon_emoji_id,
+            closed: edit.closed,
+            hidden: edit.hidden,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Delete a topic of a forum supergroup, along with all the messages posted in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let topic_id = 123;
+    ///
+    /// // Careful, the topic will be gone after the method succeeds!
+    /// client.delete_forum_topic_history(&chat, topic_id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_forum_topic_history<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        topic_id: i32,
+    ) -> Result<(), InvocationError> {
+        let channel = chat
+            .into()
+            .try_to_input_channel()
+            .ok_or_else(|| bad_request("CHANNEL_INVALID"))?;
+        loop {
+            // Telegram deletes the messages in batches, and must be asked again until done.
+            let tl::enums::messages::AffectedHistory::History(affected) = self
+                .invoke(&tl::functions::channels::DeleteTopicHistory {
+                    channel: channel.clone(),
+                    top_msg_id: topic_id,
+                })
+                .await?;
+            if affected.offset <= 0 {
+                break Ok(());
+            }
+        }
+    }
+}
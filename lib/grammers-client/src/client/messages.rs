@@ -8,18 +8,21 @@
 
 //! Methods related to sending messages.
 use crate::types::message::EMPTY_MESSAGE;
-use crate::types::{InputReactions, IterBuffer, Message};
-use crate::utils::{generate_random_id, generate_random_ids};
-use crate::{ChatMap, Client, InputMedia, types};
-use chrono::{DateTime, FixedOffset};
+use crate::types::reply_to::input_reply_to;
+use crate::types::{ForwardOptions, InputReactions, IterBuffer, Message, PeerReaction};
+use crate::utils::{self, bad_request, generate_random_id, generate_random_ids};
+use crate::{ChatMap, Client, InputMedia, InputMessage, types};
+use chrono::{DateTime, FixedOffset, Utc};
+use grammers_mtsender::Priority;
 pub use grammers_mtsender::{AuthorizationError, InvocationError};
 use grammers_session::PackedChat;
 use grammers_tl_types as tl;
 use log::{Level, log_enabled, warn};
 use std::collections::HashMap;
 use tl::enums::InputPeer;
+use web_time::SystemTime;
 
-fn map_random_ids_to_messages(
+pub(crate) fn map_random_ids_to_messages(
     client: &Client,
     random_ids: &[i64],
     updates: tl::enums::Updates,
@@ -128,7 +131,11 @@ impl<R: tl::RemoteCall<Return = tl::enums::messages::Messages>> IterBuffer<R, Me
 
         use tl::enums::messages::Messages;
 
-        let total = match self.client.invoke(&self.request).await? {
+        let total = match self
+            .client
+            .invoke_with_priority(&self.request, Priority::Low)
+            .await?
+        {
             Messages::Messages(messages) => messages.messages.len(),
             Messages::Slice(messages) => messages.count as usize,
             Messages::ChannelMessages(messages) => messages.count as usize,
@@ -144,7 +151,11 @@ impl<R: tl::RemoteCall<Return = tl::enums::messages::Messages>> IterBuffer<R, Me
     async fn fill_buffer(&mut self, limit: i32) -> Result<Option<i32>, InvocationError> {
         use tl::enums::messages::Messages;
 
-        let (messages, users, chats, rate) = match self.client.invoke(&self.request).await? {
+        let (messages, users, chats, rate) = match self
+            .client
+            .invoke_with_priority(&self.request, Priority::Low)
+            .await?
+        {
             Messages::Messages(m) => {
                 self.last_chunk = true;
                 self.total = Some(m.messages.len());
@@ -451,6 +462,309 @@ impl GlobalSearchIter {
     }
 }
 
+pub type ScheduledMessageIter = IterBuffer<tl::functions::messages::GetScheduledHistory, Message>;
+
+impl ScheduledMessageIter {
+    fn new(client: &Client, peer: PackedChat) -> Self {
+        Self::from_request(
+            client,
+            MAX_LIMIT,
+            tl::functions::messages::GetScheduledHistory {
+                peer: peer.to_input_peer(),
+                hash: 0,
+            },
+        )
+    }
+
+    /// Determines how many scheduled messages there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        self.get_total().await
+    }
+
+    /// Return the next `Message` from the internal buffer, filling the buffer previously if it's
+    /// empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no scheduled messages left.
+    pub async fn next(&mut self) -> Result<Option<Message>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        // All scheduled messages are returned at once.
+        self.fill_buffer(0).await?;
+        self.last_chunk = true;
+
+        Ok(self.pop_item())
+    }
+}
+
+pub type ReplyIter = IterBuffer<tl::functions::messages::GetReplies, Message>;
+
+impl ReplyIter {
+    fn new(client: &Client, peer: PackedChat, message_id: i32) -> Self {
+        Self::from_request(
+            client,
+            MAX_LIMIT,
+            tl::functions::messages::GetReplies {
+                peer: peer.to_input_peer(),
+                msg_id: message_id,
+                offset_id: 0,
+                offset_date: 0,
+                add_offset: 0,
+                limit: 0,
+                max_id: 0,
+                min_id: 0,
+                hash: 0,
+            },
+        )
+    }
+
+    /// Determines how many replies there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        self.request.limit = 1;
+        self.get_total().await
+    }
+
+    /// Return the next `Message` from the internal buffer, filling the buffer previously if it's
+    /// empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no replies left.
+    pub async fn next(&mut self) -> Result<Option<Message>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_LIMIT);
+        self.fill_buffer(self.request.limit).await?;
+
+        // Don't bother updating offsets if this is the last time stuff has to be fetched.
+        if !self.last_chunk && !self.buffer.is_empty() {
+            let last = &self.buffer[self.buffer.len() - 1];
+            self.request.offset_id = last.raw.id;
+            self.request.offset_date = last.raw.date;
+        }
+
+        Ok(self.pop_item())
+    }
+}
+
+/// Requests that list the messages of a chat that have something unread, such as mentions or
+/// reactions, and are paginated by message identifier alone.
+trait UnreadRequest: tl::RemoteCall<Return = tl::enums::messages::Messages> {
+    fn set_limit(&mut self, limit: i32);
+    fn set_offset_id(&mut self, offset_id: i32);
+}
+
+impl UnreadRequest for tl::functions::messages::GetUnreadMentions {
+    fn set_limit(&mut self, limit: i32) {
+        self.limit = limit;
+    }
+
+    fn set_offset_id(&mut self, offset_id: i32) {
+        self.offset_id = offset_id;
+    }
+}
+
+impl UnreadRequest for tl::functions::messages::GetUnreadReactions {
+    fn set_limit(&mut self, limit: i32) {
+        self.limit = limit;
+    }
+
+    fn set_offset_id(&mut self, offset_id: i32) {
+        self.offset_id = offset_id;
+    }
+}
+
+/// Fetches the total number of messages with something unread unless cached.
+async fn unread_total<R: UnreadRequest>(
+    iter: &mut IterBuffer<R, Message>,
+) -> Result<usize, InvocationError> {
+    iter.request.set_limit(1);
+    iter.get_total().await
+}
+
+/// Returns the next message with something unread, filling the buffer if it's empty.
+async fn next_unread<R: UnreadRequest>(
+    iter: &mut IterBuffer<R, Message>,
+) -> Result<Option<Message>, InvocationError> {
+    if let Some(result) = iter.next_raw() {
+        return result;
+    }
+
+    let limit = iter.determine_limit(MAX_LIMIT);
+    iter.request.set_limit(limit);
+    iter.fill_buffer(limit).await?;
+
+    // Don't bother updating offsets if this is the last time stuff has to be fetched.
+    if !iter.last_chunk && !iter.buffer.is_empty() {
+        let offset_id = iter.buffer[iter.buffer.len() - 1].raw.id;
+        iter.request.set_offset_id(offset_id);
+    }
+
+    Ok(iter.pop_item())
+}
+
+pub type UnreadMentionIter = IterBuffer<tl::functions::messages::GetUnreadMentions, Message>;
+
+impl UnreadMentionIter {
+    fn new(client: &Client, peer: PackedChat) -> Self {
+        Self::from_request(
+            client,
+            MAX_LIMIT,
+            tl::functions::messages::GetUnreadMentions {
+                peer: peer.to_input_peer(),
+                top_msg_id: None,
+                offset_id: 0,
+                add_offset: 0,
+                limit: 0,
+                max_id: 0,
+                min_id: 0,
+            },
+        )
+    }
+
+    /// Only return the mentions within the given forum topic.
+    pub fn topic(mut self, topic_id: i32) -> Self {
+        self.request.top_msg_id = Some(topic_id);
+        self
+    }
+
+    /// Determines how many mentions there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        unread_total(self).await
+    }
+
+    /// Return the next `Message` from the internal buffer, filling the buffer previously if it's
+    /// empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no mentions left.
+    pub async fn next(&mut self) -> Result<Option<Message>, InvocationError> {
+        next_unread(self).await
+    }
+}
+
+pub type UnreadReactionIter = IterBuffer<tl::functions::messages::GetUnreadReactions, Message>;
+
+impl UnreadReactionIter {
+    fn new(client: &Client, peer: PackedChat) -> Self {
+        Self::from_request(
+            client,
+            MAX_LIMIT,
+            tl::functions::messages::GetUnreadReactions {
+                peer: peer.to_input_peer(),
+                top_msg_id: None,
+                offset_id: 0,
+                add_offset: 0,
+                limit: 0,
+                max_id: 0,
+                min_id: 0,
+            },
+        )
+    }
+
+    /// Only return the messages with unread reactions within the given forum topic.
+    pub fn topic(mut self, topic_id: i32) -> Self {
+        self.request.top_msg_id = Some(topic_id);
+        self
+    }
+
+    /// Determines how many messages with unread reactions there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        unread_total(self).await
+    }
+
+    /// Return the next `Message` from the internal buffer, filling the buffer previously if it's
+    /// empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no messages with unread reactions left.
+    pub async fn next(&mut self) -> Result<Option<Message>, InvocationError> {
+        next_unread(self).await
+    }
+}
+
+pub type MessageReactorIter =
+    IterBuffer<tl::functions::messages::GetMessageReactionsList, PeerReaction>;
+
+impl MessageReactorIter {
+    fn new(
+        client: &Client,
+        chat: PackedChat,
+        message_id: i32,
+        reaction: Option<tl::enums::Reaction>,
+    ) -> Self {
+        Self::from_request(
+            client,
+            MAX_LIMIT,
+            tl::functions::messages::GetMessageReactionsList {
+                peer: chat.to_input_peer(),
+                id: message_id,
+                reaction,
+                offset: None,
+                limit: 0,
+            },
+        )
+    }
+
+    /// Determines how many reactions there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.request.limit = 1;
+        let tl::enums::messages::MessageReactionsList::List(list) =
+            self.client.invoke(&self.request).await?;
+        let total = list.count as usize;
+        self.total = Some(total);
+        Ok(total)
+    }
+
+    /// Return the next `PeerReaction` from the internal buffer, filling the buffer previously if
+    /// it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no reactions left.
+    pub async fn next(&mut self) -> Result<Option<PeerReaction>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_LIMIT);
+        let tl::enums::messages::MessageReactionsList::List(list) =
+            self.client.invoke(&self.request).await?;
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&list.users, &list.chats);
+        }
+
+        self.total = Some(list.count as usize);
+        self.request.offset = list.next_offset;
+        if self.request.offset.is_none() {
+            self.last_chunk = true;
+        }
+
+        let chats = ChatMap::new(list.users, list.chats);
+        self.buffer.extend(
+            list.reactions
+                .into_iter()
+                .map(|reaction| PeerReaction::from_raw(reaction, &chats, &self.client)),
+        );
+
+        Ok(self.pop_item())
+    }
+}
+
 /// Method implementations related to sending, modifying or getting messages.
 impl Client {
     /// Sends a message to the desired chat.
@@ -494,30 +808,20 @@ impl Client {
                 background: message.background,
                 clear_draft: message.clear_draft,
                 peer: chat.to_input_peer(),
-                reply_to: message.reply_to.map(|reply_to_msg_id| {
-                    tl::types::InputReplyToMessage {
-                        reply_to_msg_id,
-                        top_msg_id: None,
-                        reply_to_peer_id: None,
-                        quote_text: None,
-                        quote_entities: None,
-                        quote_offset: None,
-                    }
-                    .into()
-                }),
+                reply_to: input_reply_to(message.reply_to.as_ref(), message.topic_id),
                 media,
                 message: message.text.clone(),
                 random_id,
                 reply_markup: message.reply_markup.clone(),
                 entities,
                 schedule_date: message.schedule_date,
-                send_as: None,
-                noforwards: false,
+                send_as: message.send_as.map(|send_as| send_as.to_input_peer()),
+                noforwards: message.noforwards,
                 update_stickersets_order: false,
                 invert_media: message.invert_media,
-                quick_reply_shortcut: None,
-                effect: None,
-                allow_paid_floodskip: false,
+                quick_reply_shortcut: message.quick_reply_shortcut.clone(),
+                effect: message.effect,
+                allow_paid_floodskip: message.allow_paid_floodskip,
             })
             .await
         } else {
@@ -527,29 +831,19 @@ impl Client {
                 background: message.background,
                 clear_draft: message.clear_draft,
                 peer: chat.to_input_peer(),
-                reply_to: message.reply_to.map(|reply_to_msg_id| {
-                    tl::types::InputReplyToMessage {
-                        reply_to_msg_id,
-                        top_msg_id: None,
-                        reply_to_peer_id: None,
-                        quote_text: None,
-                        quote_entities: None,
-                        quote_offset: None,
-                    }
-                    .into()
-                }),
+                reply_to: input_reply_to(message.reply_to.as_ref(), message.topic_id),
                 message: message.text.clone(),
                 random_id,
                 reply_markup: message.reply_markup.clone(),
                 entities,
                 schedule_date: message.schedule_date,
-                send_as: None,
-                noforwards: false,
+                send_as: message.send_as.map(|send_as| send_as.to_input_peer()),
+                noforwards: message.noforwards,
                 update_stickersets_order: false,
                 invert_media: message.invert_media,
-                quick_reply_shortcut: None,
-                effect: None,
-                allow_paid_floodskip: false,
+                quick_reply_shortcut: message.quick_reply_shortcut.clone(),
+                effect: message.effect,
+                allow_paid_floodskip: message.allow_paid_floodskip,
             })
             .await
         }?;
@@ -584,6 +878,53 @@ impl Client {
         })
     }
 
+    /// Get the chats that messages can be sent as in the given group or channel, such as the
+    /// logged-in account itself, channels it owns, or the group itself when anonymous.
+    ///
+    /// Some of the returned chats may only be usable by accounts with Telegram Premium.
+    ///
+    /// See also: [`InputMessage::send_as`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::InputMessage;
+    ///
+    /// let send_as = client.get_send_as_peers(&chat).await?.pop();
+    /// client
+    ///     .send_message(&chat, InputMessage::text("Hello!").send_as(send_as.as_ref()))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`InputMessage::send_as`]: crate::InputMessage::send_as
+    pub async fn get_send_as_peers<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+    ) -> Result<Vec<types::Chat>, InvocationError> {
+        let tl::enums::channels::SendAsPeers::Peers(peers) = self
+            .invoke(&tl::functions::channels::GetSendAs {
+                for_paid_reactions: false,
+                peer: chat.into().to_input_peer(),
+            })
+            .await?;
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&peers.users, &peers.chats);
+        }
+
+        let chats = ChatMap::new(peers.users, peers.chats);
+        Ok(peers
+            .peers
+            .into_iter()
+            .filter_map(|tl::enums::SendAsPeer::Peer(peer)| chats.get(&peer.peer).cloned())
+            .collect())
+    }
+
     /// Sends a album to the desired chat.
     ///
     /// This method can also be used to send a bunch of media such as photos, videos, documents, polls, etc.
@@ -643,6 +984,9 @@ impl Client {
         }
 
         let first_media = medias.first().unwrap();
+        let reply_to = input_reply_to(first_media.reply_to.as_ref(), first_media.topic_id);
+        let send_as = first_media.send_as.map(|send_as| send_as.to_input_peer());
+        let noforwards = first_media.noforwards;
 
         let updates = self
             .invoke(&tl::functions::messages::SendMultiMedia {
@@ -650,17 +994,7 @@ impl Client {
                 background: false,
                 clear_draft: false,
                 peer: chat.to_input_peer(),
-                reply_to: first_media.reply_to.map(|reply_to_msg_id| {
-                    tl::types::InputReplyToMessage {
-                        reply_to_msg_id,
-                        top_msg_id: None,
-                        reply_to_peer_id: None,
-                        quote_text: None,
-                        quote_entities: None,
-                        quote_offset: None,
-                    }
-                    .into()
-                }),
+                reply_to,
                 schedule_date: None,
                 multi_media: medias
                     .into_iter()
@@ -677,8 +1011,8 @@ impl Client {
                         })
                     })
                     .collect(),
-                send_as: None,
-                noforwards: false,
+                send_as,
+                noforwards,
                 update_stickersets_order: false,
                 invert_media: false,
                 quick_reply_shortcut: None,
@@ -734,20 +1068,54 @@ impl Client {
         Ok(())
     }
 
-    /// Deletes up to 100 messages in a chat.
+    /// Edits a scheduled message, and changes when it will be sent.
     ///
-    /// <div class="stab unstable">
-    ///
-    /// **Warning**: when deleting messages from small group chats or private conversations, this
-    /// method cannot validate that the provided message IDs actually belong to the input chat due
-    /// to the way Telegram's API works. Make sure to pass correct [`Message::id`]'s.
+    /// The `schedule_date` of the new message is ignored in favour of the one given here. Use
+    /// the original date of the message to change its contents only.
     ///
-    /// </div>
+    /// # Examples
     ///
-    /// The messages are deleted for both ends.
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::{Duration, SystemTime};
     ///
-    /// The amount of deleted messages is returned (it might be less than the amount of input
-    /// message IDs if some of them were already missing). It is not possible to find out which
+    /// let scheduled_message_id = 123;
+    /// let in_an_hour = SystemTime::now() + Duration::from_secs(60 * 60);
+    /// client
+    ///     .edit_scheduled_message(&chat, scheduled_message_id, "Postponed message", in_an_hour)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn edit_scheduled_message<C: Into<PackedChat>, M: Into<types::InputMessage>>(
+        &self,
+        chat: C,
+        message_id: i32,
+        new_message: M,
+        schedule_date: SystemTime,
+    ) -> Result<(), InvocationError> {
+        self.edit_message(
+            chat,
+            message_id,
+            new_message.into().schedule_date(Some(schedule_date)),
+        )
+        .await
+    }
+
+    /// Deletes up to 100 messages in a chat.
+    ///
+    /// <div class="stab unstable">
+    ///
+    /// **Warning**: when deleting messages from small group chats or private conversations, this
+    /// method cannot validate that the provided message IDs actually belong to the input chat due
+    /// to the way Telegram's API works. Make sure to pass correct [`Message::id`]'s.
+    ///
+    /// </div>
+    ///
+    /// The messages are deleted for both ends.
+    ///
+    /// The amount of deleted messages is returned (it might be less than the amount of input
+    /// message IDs if some of them were already missing). It is not possible to find out which
     /// messages were actually deleted, but if the request succeeds, none of the specified message
     /// IDs will appear in the message history from that point on.
     ///
@@ -797,7 +1165,7 @@ impl Client {
     /// message IDs, and the indices from the list of IDs map to the indices in the result so
     /// you can find which messages were forwarded and which message they became.
     ///
-    /// See also: [`Message::forward_to`].
+    /// See also: [`Message::forward_to`], [`Client::forward_messages_with_options`].
     ///
     /// # Examples
     ///
@@ -817,21 +1185,58 @@ impl Client {
         message_ids: &[i32],
         source: S,
     ) -> Result<Vec<Option<Message>>, InvocationError> {
-        // TODO let user customize more options
+        self.forward_messages_with_options(
+            destination,
+            message_ids,
+            source,
+            ForwardOptions::default(),
+        )
+        .await
+    }
+
+    /// Forwards up to 100 messages from `source` into `destination`, customizing how they are
+    /// forwarded.
+    ///
+    /// Refer to [`ForwardOptions`] to learn about the available options. The result is the same
+    /// as that of [`Client::forward_messages`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(destination: grammers_client::types::Chat, source: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::ForwardOptions;
+    ///
+    /// // Forward the messages without the name of their author, and without notifying anyone.
+    /// let options = ForwardOptions::new().drop_author(true).silent(true);
+    /// client
+    ///     .forward_messages_with_options(&destination, &[123, 456], &source, options)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ForwardOptions`]: crate::types::ForwardOptions
+    pub async fn forward_messages_with_options<C: Into<PackedChat>, S: Into<PackedChat>>(
+        &self,
+        destination: C,
+        message_ids: &[i32],
+        source: S,
+        options: ForwardOptions,
+    ) -> Result<Vec<Option<Message>>, InvocationError> {
         let request = tl::functions::messages::ForwardMessages {
-            silent: false,
-            background: false,
-            with_my_score: false,
-            drop_author: false,
-            drop_media_captions: false,
+            silent: options.silent,
+            background: options.background,
+            with_my_score: options.with_my_score,
+            drop_author: options.drop_author,
+            drop_media_captions: options.drop_media_captions,
             from_peer: source.into().to_input_peer(),
             id: message_ids.to_vec(),
             random_id: generate_random_ids(message_ids.len()),
             to_peer: destination.into().to_input_peer(),
-            top_msg_id: None,
-            schedule_date: None,
-            send_as: None,
-            noforwards: false,
+            top_msg_id: options.topic_id,
+            schedule_date: options.schedule_date,
+            send_as: options.send_as.map(|send_as| send_as.to_input_peer()),
+            noforwards: options.noforwards,
             quick_reply_shortcut: None,
             allow_paid_floodskip: false,
             video_timestamp: None,
@@ -840,6 +1245,121 @@ impl Client {
         Ok(map_random_ids_to_messages(self, &request.random_id, result))
     }
 
+    /// Sends copies of the given messages into `destination`, without the header saying that
+    /// they were forwarded.
+    ///
+    /// The text, formatting entities and media of each message are sent again. Consecutive
+    /// messages belonging to the same album are sent as an album. Media that cannot be sent
+    /// again is left out, so only the text of such messages is copied.
+    ///
+    /// Returns the new messages, with the indices of the input messages mapping to the indices
+    /// of the result. As with [`Client::send_album`], messages of an album that could not be
+    /// found in the response will be `None`.
+    ///
+    /// See also: [`Message::copy_to`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(destination: grammers_client::types::Chat, source: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let messages = client
+    ///     .get_messages_by_id(&source, &[123, 124, 125])
+    ///     .await?
+    ///     .into_iter()
+    ///     .flatten()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// client.copy_messages(&destination, &messages).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_messages<C: Into<PackedChat>>(
+        &self,
+        destination: C,
+        messages: &[Message],
+    ) -> Result<Vec<Option<Message>>, InvocationError> {
+        self.copy_messages_with_options(destination, messages, ForwardOptions::default())
+            .await
+    }
+
+    /// Like [`Client::copy_messages`], but customizing how the copies are sent.
+    ///
+    /// Only the [`ForwardOptions::noforwards`], [`ForwardOptions::send_as`] and
+    /// [`ForwardOptions::topic`] options apply to copies, and the rest are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(destination: grammers_client::types::Chat, channel: grammers_client::types::Chat, messages: Vec<grammers_client::types::Message>, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::ForwardOptions;
+    ///
+    /// // Copy the messages on behalf of a channel, and prevent them from being forwarded.
+    /// let options = ForwardOptions::new().send_as(Some(&channel)).noforwards(true);
+    /// client
+    ///     .copy_messages_with_options(&destination, &messages, options)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ForwardOptions::noforwards`]: crate::types::ForwardOptions::noforwards
+    /// [`ForwardOptions::send_as`]: crate::types::ForwardOptions::send_as
+    /// [`ForwardOptions::topic`]: crate::types::ForwardOptions::topic
+    pub async fn copy_messages_with_options<C: Into<PackedChat>>(
+        &self,
+        destination: C,
+        messages: &[Message],
+        options: ForwardOptions,
+    ) -> Result<Vec<Option<Message>>, InvocationError> {
+        let destination = destination.into();
+        let mut result = Vec::with_capacity(messages.len());
+        let mut remaining = messages;
+
+        while let Some(first) = remaining.first() {
+            // Only media that can be sent again can be part of an album.
+            let album_len = match first.grouped_id() {
+                Some(grouped_id) => remaining
+                    .iter()
+                    .take_while(|message| {
+                        message.grouped_id() == Some(grouped_id)
+                            && message
+                                .media()
+                                .and_then(|media| media.to_raw_input_media())
+                                .is_some()
+                    })
+                    .count(),
+                None => 0,
+            };
+
+            if album_len > 1 {
+                let (album, rest) = remaining.split_at(album_len);
+                let medias = album
+                    .iter()
+                    .map(|message| {
+                        let mut media = InputMedia::caption(message.text())
+                            .fmt_entities(message.fmt_entities().cloned().unwrap_or_default())
+                            .copy_media(&message.media().unwrap())
+                            .send_as(options.send_as)
+                            .noforwards(options.noforwards);
+                        media.topic_id = options.topic_id;
+                        media
+                    })
+                    .collect();
+                result.extend(self.send_album(destination, medias).await?);
+                remaining = rest;
+            } else {
+                let mut message = InputMessage::from(first)
+                    .send_as(options.send_as)
+                    .noforwards(options.noforwards);
+                message.topic_id = options.topic_id;
+                result.push(Some(self.send_message(destination, message).await?));
+                remaining = &remaining[1..];
+            }
+        }
+
+        Ok(result)
+    }
+
     /// Gets the [`Message`] to which the input message is replying to.
     ///
     /// See also: [`Message::get_reply`].
@@ -1226,4 +1746,442 @@ impl Client {
 
         Ok(())
     }
+
+    /// Iterate over the replies in the thread started by the message with the given identifier,
+    /// most recent first.
+    ///
+    /// For posts in a broadcast channel, these are the comments posted in its linked discussion
+    /// group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    /// let mut replies = client.iter_replies(&chat, message_id);
+    ///
+    /// while let Some(reply) = replies.next().await? {
+    ///     println!("Reply: {}", reply.text());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_replies<C: Into<PackedChat>>(&self, chat: C, message_id: i32) -> ReplyIter {
+        ReplyIter::new(self, chat.into(), message_id)
+    }
+
+    /// Get the message that starts the thread of replies to the message with the given
+    /// identifier.
+    ///
+    /// For posts in a broadcast channel, this is the automatic copy of the post in its linked
+    /// discussion group, under which comments are posted. For messages in a group, this is the
+    /// message itself.
+    ///
+    /// Returns `None` if the message has no thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    ///
+    /// if let Some(discussion) = client.get_discussion_message(&chat, message_id).await? {
+    ///     println!("Comments are in the chat with ID {}", discussion.chat().id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_discussion_message<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+    ) -> Result<Option<Message>, InvocationError> {
+        let tl::enums::messages::DiscussionMessage::Message(discussion) = self
+            .invoke(&tl::functions::messages::GetDiscussionMessage {
+                peer: chat.into().to_input_peer(),
+                msg_id: message_id,
+            })
+            .await?;
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state
+                .chat_hashes
+                .extend(&discussion.users, &discussion.chats);
+        }
+
+        let chats = ChatMap::new(discussion.users, discussion.chats);
+        // Albums are returned as multiple messages, and the thread belongs to the first one.
+        Ok(discussion
+            .messages
+            .into_iter()
+            .flat_map(|message| Message::from_raw(self, message, &chats))
+            .min_by_key(|message| message.id()))
+    }
+
+    /// Post a message in the thread of replies to the message with the given identifier.
+    ///
+    /// For posts in a broadcast channel, the message is sent as a comment in its linked
+    /// discussion group. The message can still reply to another message in the thread with
+    /// [`InputMessage::reply_to`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let post_id = 123;
+    ///
+    /// client.reply_in_thread(&chat, post_id, "First!").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`InputMessage::reply_to`]: crate::InputMessage::reply_to
+    pub async fn reply_in_thread<C: Into<PackedChat>, M: Into<types::InputMessage>>(
+        &self,
+        chat: C,
+        message_id: i32,
+        message: M,
+    ) -> Result<Message, InvocationError> {
+        let discussion = self
+            .get_discussion_message(chat, message_id)
+            .await?
+            .ok_or_else(|| bad_request("MSG_ID_INVALID"))?;
+
+        // Replies in a thread point to the message that started it, like those in forum topics,
+        // but the thread is not a topic, so the reply is set directly rather than with `topic_id`.
+        let mut message = message.into();
+        message.reply_to = input_reply_to(message.reply_to.as_ref(), Some(discussion.id()))
+            .map(|raw| types::ReplyTo { raw });
+        message.topic_id = None;
+        self.send_message(discussion.chat(), message).await
+    }
+
+    /// Mark the thread of replies to the message with the given identifier as read, up to and
+    /// including the reply with the identifier `read_max_id`.
+    ///
+    /// For posts in a broadcast channel, use the chat and identifier of the message returned by
+    /// [`Client::get_discussion_message`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    ///
+    /// if let Some(reply) = client.iter_replies(&chat, message_id).next().await? {
+    ///     client.mark_thread_as_read(&chat, message_id, reply.id()).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mark_thread_as_read<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+        read_max_id: i32,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::ReadDiscussion {
+            peer: chat.into().to_input_peer(),
+            msg_id: message_id,
+            read_max_id,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Iterate over the messages in a chat that mention the logged-in account and have not been
+    /// read yet, most recent first.
+    ///
+    /// Use [`Client::clear_mentions`] to mark all of them as read at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut mentions = client.iter_unread_mentions(&chat);
+    ///
+    /// while let Some(message) = mentions.next().await? {
+    ///     println!("Mentioned in: {}", message.text());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_unread_mentions<C: Into<PackedChat>>(&self, chat: C) -> UnreadMentionIter {
+        UnreadMentionIter::new(self, chat.into())
+    }
+
+    /// Iterate over the messages of the logged-in account in a chat which received reactions
+    /// that have not been seen yet, most recent first.
+    ///
+    /// Use [`Client::read_reactions`] to mark all of them as seen at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut messages = client.iter_unread_reactions(&chat);
+    ///
+    /// while let Some(message) = messages.next().await? {
+    ///     println!("New reactions to: {}", message.text());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_unread_reactions<C: Into<PackedChat>>(&self, chat: C) -> UnreadReactionIter {
+        UnreadReactionIter::new(self, chat.into())
+    }
+
+    /// Get who read the outgoing message with the given identifier in a small group, along with
+    /// when they read it.
+    ///
+    /// The users are returned as their identifier. Telegram only keeps this information for
+    /// recent messages in groups with few members.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    ///
+    /// for (user_id, date) in client.get_message_read_participants(&chat, message_id).await? {
+    ///     println!("User {user_id} read the message at {date}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_message_read_participants<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+    ) -> Result<Vec<(i64, DateTime<Utc>)>, InvocationError> {
+        Ok(self
+            .invoke(&tl::functions::messages::GetMessageReadParticipants {
+                peer: chat.into().to_input_peer(),
+                msg_id: message_id,
+            })
+            .await?
+            .into_iter()
+            .map(|tl::enums::ReadParticipantDate::Date(read)| {
+                (read.user_id, utils::date(read.date))
+            })
+            .collect())
+    }
+
+    /// Get when the other user read the outgoing message with the given identifier in a private
+    /// conversation.
+    ///
+    /// Telegram only shares this if neither user hides their read times in their privacy
+    /// settings, or if the logged-in account has Telegram Premium.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    ///
+    /// let date = client.get_outbox_read_date(&chat, message_id).await?;
+    /// println!("The message was read at {date}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_outbox_read_date<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+    ) -> Result<DateTime<Utc>, InvocationError> {
+        let tl::enums::OutboxReadDate::Date(read) = self
+            .invoke(&tl::functions::messages::GetOutboxReadDate {
+                peer: chat.into().to_input_peer(),
+                msg_id: message_id,
+            })
+            .await?;
+        Ok(utils::date(read.date))
+    }
+
+    /// Increment the view counter of the given messages in a channel, as official clients do
+    /// when the messages are shown.
+    ///
+    /// Returns the updated view count of each message, in the same order as the input
+    /// identifiers. The count is `None` for messages without a view counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_ids = [123, 456];
+    ///
+    /// let views = client.increment_views(&chat, &message_ids).await?;
+    /// println!("The messages now have {views:?} views");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn increment_views<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_ids: &[i32],
+    ) -> Result<Vec<Option<i32>>, InvocationError> {
+        let tl::enums::messages::MessageViews::Views(views) = self
+            .invoke(&tl::functions::messages::GetMessagesViews {
+                peer: chat.into().to_input_peer(),
+                id: message_ids.to_vec(),
+                increment: true,
+            })
+            .await?;
+
+        {
+            let mut state = self.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&views.users, &views.chats);
+        }
+
+        Ok(views
+            .views
+            .into_iter()
+            .map(|tl::enums::MessageViews::Views(views)| views.views)
+            .collect())
+    }
+
+    /// Iterate over who reacted to the message with the given identifier, most recent first,
+    /// optionally only those who used the given reaction.
+    ///
+    /// This is only possible when [`MessageReactions::can_see_list`] is `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    /// let mut reactors = client.iter_message_reactors(&chat, message_id, None);
+    ///
+    /// while let Some(reaction) = reactors.next().await? {
+    ///     println!("{} reacted with {:?}", reaction.chat().id(), reaction.reaction());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MessageReactions::can_see_list`]: crate::types::MessageReactions::can_see_list
+    pub fn iter_message_reactors<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+        filter: Option<tl::enums::Reaction>,
+    ) -> MessageReactorIter {
+        MessageReactorIter::new(self, chat.into(), message_id, filter)
+    }
+
+    /// Change which reactions can be used on the messages of a group or channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_tl_types as tl;
+    ///
+    /// // Only allow thumbs up and down.
+    /// let reactions = ["👍", "👎"]
+    ///     .into_iter()
+    ///     .map(|emoticon| {
+    ///         tl::types::ReactionEmoji {
+    ///             emoticon: emoticon.to_string(),
+    ///         }
+    ///         .into()
+    ///     })
+    ///     .collect();
+    ///
+    /// client
+    ///     .set_chat_available_reactions(&chat, tl::types::ChatReactionsSome { reactions }.into())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_chat_available_reactions<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        reactions: tl::enums::ChatReactions,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::SetChatAvailableReactions {
+            peer: chat.into().to_input_peer(),
+            available_reactions: reactions,
+            reactions_limit: None,
+            paid_enabled: None,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Iterate over the messages scheduled to be sent in a chat, such as those sent with
+    /// [`InputMessage::schedule_date`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut messages = client.iter_scheduled_messages(&chat);
+    ///
+    /// while let Some(message) = messages.next().await? {
+    ///     println!("{} will be sent at {}", message.text(), message.date());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`InputMessage::schedule_date`]: crate::InputMessage::schedule_date
+    pub fn iter_scheduled_messages<C: Into<PackedChat>>(&self, chat: C) -> ScheduledMessageIter {
+        ScheduledMessageIter::new(self, chat.into())
+    }
+
+    /// Send scheduled messages right away, instead of waiting until their scheduled date.
+    ///
+    /// The scheduled messages are deleted, and new messages with different identifiers are sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let scheduled_message_ids = [123, 456];
+    ///
+    /// client.send_scheduled_now(&chat, &scheduled_message_ids).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_scheduled_now<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_ids: &[i32],
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::SendScheduledMessages {
+            peer: chat.into().to_input_peer(),
+            id: message_ids.to_vec(),
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Delete scheduled messages, so that they are never sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let scheduled_message_ids = [123, 456];
+    ///
+    /// client.delete_scheduled_messages(&chat, &scheduled_message_ids).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_scheduled_messages<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_ids: &[i32],
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::DeleteScheduledMessages {
+            peer: chat.into().to_input_peer(),
+            id: message_ids.to_vec(),
+        })
+        .await
+        .map(drop)
+    }
 }
@@ -5,17 +5,25 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
+pub mod account;
 pub mod auth;
 pub mod bots;
 pub mod chats;
 #[allow(clippy::module_inception)]
 pub mod client;
+pub mod contacts;
 pub mod dialogs;
 pub mod files;
+pub mod forum;
 pub mod messages;
 pub mod net;
+pub mod polls;
+pub mod pool;
+pub mod rate_limit;
 pub mod updates;
 
-pub use auth::SignInError;
+pub use auth::{PasswordError, PasswordUpdate, SignInError};
 pub(crate) use client::ClientInner;
 pub use client::{Client, Config, InitParams};
+pub use pool::{AccountHealth, AccountStatus, ClientPool};
+pub use rate_limit::RateLimit;
@@ -6,23 +6,28 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 use super::client::{ClientState, Connection};
+use super::rate_limit::RateLimiter;
 use super::{Client, ClientInner, Config};
 use crate::utils;
+use futures_util::future::{Either, select};
 use grammers_mtproto::mtp;
 use grammers_mtproto::transport;
 use grammers_mtsender::ServerAddr;
 use grammers_mtsender::{
-    self as sender, AuthorizationError, InvocationError, RpcError, Sender, utils::sleep,
+    self as sender, AuthorizationError, ConnectionStats, InvocationError, Priority, QueueFull,
+    RequestChain, RpcError, Sender, utils::sleep,
 };
 use grammers_session::{ChatHashCache, MessageBox};
 use grammers_tl_types::{self as tl, Deserializable};
 use log::{debug, info};
 use sender::Enqueuer;
 use std::collections::{HashMap, VecDeque};
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::pin;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
-use tokio::sync::oneshot::error::TryRecvError;
+use std::time::Duration;
+use tokio::sync::oneshot::{self, error::TryRecvError};
 use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
 
 /// Socket addresses to Telegram datacenters, where the index into this array
@@ -55,30 +60,99 @@ const WS_ADDRESSES: [&str; 6] = [
     "wss://flora.web.telegram.org/apiws",
 ];
 
+/// The transport used by the client, chosen at runtime based on [`InitParams::use_http`].
+///
+/// [`InitParams::use_http`]: super::InitParams::use_http
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) enum Transport {
+    Full(transport::Full),
+    Http(transport::Http),
+}
+
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
-pub(crate) type Transport = transport::Full;
+impl transport::Transport for Transport {
+    fn pack(&mut self, buffer: &mut grammers_crypto::DequeBuffer<u8>) {
+        match self {
+            Self::Full(t) => t.pack(buffer),
+            Self::Http(t) => t.pack(buffer),
+        }
+    }
+
+    fn unpack(&mut self, buffer: &mut [u8]) -> Result<transport::UnpackedOffset, transport::Error> {
+        match self {
+            Self::Full(t) => t.unpack(buffer),
+            Self::Http(t) => t.unpack(buffer),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::Full(t) => t.reset(),
+            Self::Http(t) => t.reset(),
+        }
+    }
+
+    fn requires_polling(&self) -> bool {
+        match self {
+            Self::Full(t) => t.requires_polling(),
+            Self::Http(t) => t.requires_polling(),
+        }
+    }
+}
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 pub(crate) type Transport = transport::Obfuscated<transport::Intermediate>;
 
 const DEFAULT_DC: i32 = 2;
 
+/// Addresses of Telegram's datacenters as reported by the server, which take precedence over the
+/// hardcoded [`DC_ADDRESSES`]. May be shared by several clients.
+#[derive(Default)]
+pub(crate) struct DcOptions {
+    addresses: RwLock<HashMap<i32, SocketAddr>>,
+}
+
+impl DcOptions {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn get(&self, dc_id: i32) -> Option<SocketAddr> {
+        self.addresses.read().unwrap().get(&dc_id).copied()
+    }
+
+    /// Update the known addresses with the ones that can be used for a plain connection.
+    fn update(&self, options: Vec<tl::enums::DcOption>) {
+        let mut addresses = HashMap::new();
+        for tl::enums::DcOption::Option(option) in options {
+            if option.ipv6
+                || option.media_only
+                || option.tcpo_only
+                || option.cdn
+                || option.secret.is_some()
+            {
+                continue;
+            }
+            if let Ok(ip) = option.ip_address.parse::<Ipv4Addr>() {
+                addresses
+                    .entry(option.id)
+                    .or_insert_with(|| SocketAddr::from((ip, option.port as u16)));
+            }
+        }
+        self.addresses.write().unwrap().extend(addresses);
+    }
+}
+
 pub(crate) async fn connect_sender(
     dc_id: i32,
     config: &Config,
+    dc_options: &DcOptions,
 ) -> Result<(Sender<Transport, mtp::Encrypted>, Enqueuer), AuthorizationError> {
-    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
-    let transport = transport::Full::new();
-
-    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-    let transport = transport::Obfuscated::new(transport::Intermediate::new());
-
     let addr: ServerAddr = if let Some(ref sa) = config.params.server_addr {
         sa.clone()
     } else {
         #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
         let addr = {
-            let tcp_addr = DC_ADDRESSES[dc_id as usize].into();
+            let tcp_addr = dc_options
+                .get(dc_id)
+                .unwrap_or_else(|| DC_ADDRESSES[dc_id as usize].into());
 
             #[cfg(not(feature = "proxy"))]
             let addr = ServerAddr::Tcp { address: tcp_addr };
@@ -104,6 +178,21 @@ pub(crate) async fn connect_sender(
         addr
     };
 
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    let transport = if config.params.use_http {
+        let host = match addr {
+            ServerAddr::Tcp { ref address } => address.to_string(),
+            #[cfg(feature = "proxy")]
+            ServerAddr::Proxied { ref address, .. } => address.to_string(),
+        };
+        Transport::Http(transport::Http::new(host))
+    } else {
+        Transport::Full(transport::Full::new())
+    };
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    let transport = transport::Obfuscated::new(transport::Intermediate::new());
+
     let (mut sender, request_tx) = if let Some(auth_key) = config.session.dc_auth_key(dc_id) {
         info!(
             "creating a new sender with existing auth key to dc {} {:?}",
@@ -147,9 +236,14 @@ pub(crate) async fn connect_sender(
         (sender, tx)
     };
 
+    let request_tx = match config.params.request_queue_limit {
+        Some(limit) => request_tx.with_limit(limit),
+        None => request_tx,
+    };
+
     // TODO handle -404 (we had a previously-valid authkey, but server no longer knows about it)
     // TODO all up-to-date server addresses should be stored in the session for future initial connections
-    let _remote_config = sender
+    let remote_config = sender
         .invoke(&tl::functions::InvokeWithLayer {
             layer: tl::LAYER,
             query: tl::functions::InitConnection {
@@ -167,6 +261,13 @@ pub(crate) async fn connect_sender(
         })
         .await?;
 
+    if config.params.server_addr.is_none()
+        && let Ok(tl::enums::Config::Config(remote_config)) =
+            tl::enums::Config::from_bytes(&remote_config)
+    {
+        dc_options.update(remote_config.dc_options);
+    }
+
     Ok((sender, request_tx))
 }
 
@@ -200,13 +301,21 @@ impl Client {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn connect(mut config: Config) -> Result<Self, AuthorizationError> {
+    pub async fn connect(config: Config) -> Result<Self, AuthorizationError> {
+        Self::connect_with_dc_options(config, Arc::default()).await
+    }
+
+    /// Like [`Client::connect`], but reusing the datacenter addresses known by other clients.
+    pub(crate) async fn connect_with_dc_options(
+        mut config: Config,
+        dc_options: Arc<DcOptions>,
+    ) -> Result<Self, AuthorizationError> {
         let dc_id = config
             .session
             .get_user()
             .map(|u| u.dc)
             .unwrap_or(DEFAULT_DC);
-        let (sender, request_tx) = connect_sender(dc_id, &config).await?;
+        let (sender, request_tx) = connect_sender(dc_id, &config, &dc_options).await?;
         let message_box = if config.params.catch_up {
             if let Some(state) = config.session.get_state() {
                 MessageBox::load(state)
@@ -232,11 +341,11 @@ impl Client {
         }
 
         let self_user = config.session.get_user();
+        let rate_limiter = config.params.rate_limit.map(RateLimiter::new);
 
         // Don't bother getting pristine update state if we're not logged in.
         let should_get_state = message_box.is_empty() && config.session.signed_in();
 
-        // TODO Sender doesn't have a way to handle backpressure yet
         let client = Self(Arc::new(ClientInner {
             id: utils::generate_random_id(),
             config,
@@ -247,12 +356,18 @@ impl Client {
                 chat_hashes: ChatHashCache::new(self_user.map(|u| (u.id, u.bot))),
                 last_update_limit_warn: None,
                 updates,
+                login_token_updated: false,
             }),
             downloader_map: AsyncRwLock::new(HashMap::new()),
+            dc_options,
+            rate_limiter,
         }));
 
         if should_get_state {
-            match client.invoke(&tl::functions::updates::GetState {}).await {
+            match client
+                .invoke_unlimited(&tl::functions::updates::GetState {})
+                .await
+            {
                 Ok(state) => {
                     {
                         client.0.state.write().unwrap().message_box.set_state(state);
@@ -296,11 +411,192 @@ impl Client {
         &self,
         request: &R,
     ) -> Result<R::Return, InvocationError> {
+        self.wait_rate_limit().await;
+        self.invoke_unlimited(request).await
+    }
+
+    /// Like [`Client::invoke`], but without waiting for the [`InitParams::rate_limit`].
+    ///
+    /// Used by requests the library makes on its own, such as fetching missed updates, which
+    /// should not compete with the requests made by the user.
+    ///
+    /// [`InitParams::rate_limit`]: super::InitParams::rate_limit
+    pub(crate) async fn invoke_unlimited<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+    ) -> Result<R::Return, InvocationError> {
+        self.0
+            .conn
+            .invoke(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                Priority::Normal,
+                None,
+                |updates| self.process_socket_updates(updates),
+            )
+            .await
+    }
+
+    /// Like [`Client::invoke`], but fails with [`InvocationError::Timeout`] if the response does
+    /// not arrive before the given timeout, overriding [`InitParams::request_timeout`].
+    ///
+    /// The timeout includes any time spent sleeping on flood waits. When the timeout elapses, the
+    /// request is cancelled: if it was not sent yet, it never will be, and if it was, Telegram
+    /// will be asked to drop its answer (although the request may still have been processed).
+    ///
+    /// Dropping the future returned by any of the `invoke` methods cancels the request in the
+    /// same way.
+    ///
+    /// <div class="stab unstable">
+    ///
+    /// **Warning**: this method is **not** part of the stability guarantees of semantic
+    /// versioning. It **may** break during *minor* version changes (but not on patch version
+    /// changes). Use with care.
+    ///
+    /// </div>
+    ///
+    /// [`InitParams::request_timeout`]: crate::InitParams::request_timeout
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_tl_types as tl;
+    /// use std::time::Duration;
+    ///
+    /// let ping = tl::functions::Ping { ping_id: 0 };
+    /// dbg!(client.invoke_with_timeout(&ping, Duration::from_secs(5)).await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn invoke_with_timeout<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+        timeout: Duration,
+    ) -> Result<R::Return, InvocationError> {
+        self.wait_rate_limit().await;
         self.0
             .conn
             .invoke(
                 request,
                 self.0.config.params.flood_sleep_threshold,
+                Some(timeout),
+                Priority::Normal,
+                None,
+                |updates| self.process_socket_updates(updates),
+            )
+            .await
+    }
+
+    /// Like [`Client::invoke`], but the request is sent with the given [`Priority`].
+    ///
+    /// Requests with a higher priority are sent before any pending request with a lower priority,
+    /// which is useful to prevent bulk operations from delaying more important requests. Methods
+    /// such as [`Client::iter_download`] already use [`Priority::Low`].
+    ///
+    /// <div class="stab unstable">
+    ///
+    /// **Warning**: this method is **not** part of the stability guarantees of semantic
+    /// versioning. It **may** break during *minor* version changes (but not on patch version
+    /// changes). Use with care.
+    ///
+    /// </div>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::Priority;
+    /// use grammers_tl_types as tl;
+    ///
+    /// let ping = tl::functions::Ping { ping_id: 0 };
+    /// dbg!(client.invoke_with_priority(&ping, Priority::High).await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn invoke_with_priority<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+        priority: Priority,
+    ) -> Result<R::Return, InvocationError> {
+        self.wait_rate_limit().await;
+        self.0
+            .conn
+            .invoke(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                priority,
+                None,
+                |updates| self.process_socket_updates(updates),
+            )
+            .await
+    }
+
+    /// Like [`Client::invoke`], but Telegram will only process the request after all the
+    /// requests previously invoked with the same [`RequestChain`] have been processed.
+    ///
+    /// Unlike awaiting each request before invoking the next, this does not need to wait for a
+    /// round trip to the server between each request, while still guaranteeing their order (for
+    /// example, so that messages appear in a chat in the same order they were sent). This works
+    /// by wrapping the requests in `invokeAfterMsg`.
+    ///
+    /// Requests are ordered by the time the futures are first polled. Ordered requests still wait
+    /// for the [`InitParams::rate_limit`] in that same order, but are enqueued without waiting for
+    /// room in the [`InitParams::request_queue_limit`], as that could let later requests in the
+    /// chain go first. If a request has to be retried after sleeping on a flood wait, it will be
+    /// enqueued again after the rest of the chain.
+    ///
+    /// <div class="stab unstable">
+    ///
+    /// **Warning**: this method is **not** part of the stability guarantees of semantic
+    /// versioning. It **may** break during *minor* version changes (but not on patch version
+    /// changes). Use with care.
+    ///
+    /// </div>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::RequestChain;
+    /// use grammers_tl_types as tl;
+    ///
+    /// let chain = RequestChain::new();
+    /// let first = tl::functions::Ping { ping_id: 1 };
+    /// let second = tl::functions::Ping { ping_id: 2 };
+    ///
+    /// // Both requests are sent at once, but the second is processed after the first.
+    /// let (first, second) = futures_util::future::join(
+    ///     client.invoke_ordered(&first, &chain),
+    ///     client.invoke_ordered(&second, &chain),
+    /// )
+    /// .await;
+    /// dbg!(first?, second?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`InitParams::rate_limit`]: super::InitParams::rate_limit
+    /// [`InitParams::request_queue_limit`]: super::InitParams::request_queue_limit
+    pub async fn invoke_ordered<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+        chain: &RequestChain,
+    ) -> Result<R::Return, InvocationError> {
+        // Nothing may be awaited between taking the turn and enqueueing into the chain.
+        let rx = self
+            .wait_rate_limit_then(|| self.0.conn.enqueue_in_chain(request, *chain))
+            .await;
+        self.0
+            .conn
+            .invoke_in_chain(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                *chain,
+                rx,
                 |updates| self.process_socket_updates(updates),
             )
             .await
@@ -313,7 +609,7 @@ impl Client {
         let request = tl::functions::auth::ExportAuthorization {
             dc_id: target_dc_id,
         };
-        match self.invoke(&request).await {
+        match self.invoke_unlimited(&request).await {
             Ok(tl::enums::auth::ExportedAuthorization::Authorization(exported_auth)) => {
                 Ok(exported_auth)
             }
@@ -324,7 +620,7 @@ impl Client {
     async fn connect_sender(&self, dc_id: i32) -> Result<Arc<Connection>, InvocationError> {
         let mut mutex = self.0.downloader_map.write().await;
         debug!("Connecting new datacenter {}", dc_id);
-        match connect_sender(dc_id, &self.0.config).await {
+        match connect_sender(dc_id, &self.0.config, &self.0.dc_options).await {
             Ok((new_sender, new_tx)) => {
                 let new_downloader = Arc::new(Connection::new(new_sender, new_tx));
 
@@ -337,7 +633,14 @@ impl Client {
                     bytes: authorization.bytes,
                 };
                 new_downloader
-                    .invoke(&request, self.0.config.params.flood_sleep_threshold, drop)
+                    .invoke(
+                        &request,
+                        self.0.config.params.flood_sleep_threshold,
+                        self.0.config.params.request_timeout,
+                        Priority::Normal,
+                        None,
+                        drop,
+                    )
                     .await?;
 
                 mutex.insert(dc_id, new_downloader.clone());
@@ -362,15 +665,70 @@ impl Client {
         request: &R,
         dc_id: i32,
     ) -> Result<R::Return, InvocationError> {
+        self.wait_rate_limit().await;
         let downloader = match self.get_downloader(dc_id).await? {
             None => self.connect_sender(dc_id).await?,
             Some(fd) => fd,
         };
         downloader
-            .invoke(request, self.0.config.params.flood_sleep_threshold, drop)
+            .invoke(
+                request,
+                self.0.config.params.flood_sleep_threshold,
+                self.0.config.params.request_timeout,
+                Priority::Normal,
+                None,
+                drop,
+            )
             .await
     }
 
+    /// Wait until the [`InitParams::rate_limit`] allows making another request, if any.
+    ///
+    /// [`InitParams::rate_limit`]: super::InitParams::rate_limit
+    async fn wait_rate_limit(&self) {
+        self.wait_rate_limit_then(|| ()).await
+    }
+
+    /// Like [`Client::wait_rate_limit`], but `f` runs before any later request can take its turn.
+    async fn wait_rate_limit_then<T>(&self, f: impl FnOnce() -> T) -> T {
+        match &self.0.rate_limiter {
+            Some(limiter) => limiter.acquire_then(f).await,
+            None => f(),
+        }
+    }
+
+    /// Take a snapshot of the statistics of the connection to the home datacenter, which can be
+    /// used to monitor its health.
+    ///
+    /// The statistics are reset when the client migrates to a different datacenter, such as
+    /// during sign in. Connections to other datacenters made to download files are not included.
+    ///
+    /// If the `metrics` feature is enabled, the same statistics are also reported through the
+    /// [`metrics`](https://docs.rs/metrics) facade as they change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let stats = client.stats();
+    /// if let Some(rtt) = stats.rtt {
+    ///     println!("round-trip time: {:?}", rtt);
+    /// }
+    /// for (method, method_stats) in stats.methods.iter() {
+    ///     println!(
+    ///         "{}: p99 latency {:?}, {} errors",
+    ///         method,
+    ///         method_stats.latency.quantile(0.99),
+    ///         method_stats.rpc_errors
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> ConnectionStats {
+        self.0.conn.stats.read().unwrap().snapshot()
+    }
+
     /// Perform a single network step.
     ///
     /// Most commonly, you will want to use the higher-level abstraction [`Client::next_update`]
@@ -416,21 +774,119 @@ impl Client {
 impl Connection {
     fn new(sender: Sender<Transport, mtp::Encrypted>, request_tx: Enqueuer) -> Self {
         Self {
+            stats: RwLock::new(sender.stats()),
             sender: AsyncMutex::new(sender),
             request_tx: RwLock::new(request_tx),
             step_counter: AtomicU32::new(0),
         }
     }
 
+    /// Replace the sender used by this connection, such as after migrating to a different
+    /// datacenter.
+    pub(crate) async fn replace(
+        &self,
+        sender: Sender<Transport, mtp::Encrypted>,
+        request_tx: Enqueuer,
+    ) {
+        let stats = sender.stats();
+        *self.sender.lock().await = sender;
+        *self.request_tx.write().unwrap() = request_tx;
+        *self.stats.write().unwrap() = stats;
+    }
+
     pub(crate) async fn invoke<R: tl::RemoteCall, F: Fn(Vec<tl::enums::Updates>)>(
         &self,
         request: &R,
         flood_sleep_threshold: u32,
+        timeout: Option<Duration>,
+        priority: Priority,
+        chain: Option<RequestChain>,
+        on_updates: F,
+    ) -> Result<R::Return, InvocationError> {
+        let invoke = self.invoke_until_done(
+            request,
+            flood_sleep_threshold,
+            priority,
+            chain,
+            None,
+            on_updates,
+        );
+        Self::with_timeout::<R>(invoke, timeout).await
+    }
+
+    /// Enqueue the request at the end of the chain right away. Unlike [`Connection::invoke`],
+    /// this does not wait for room in the request queue, which could reorder the chain.
+    pub(crate) fn enqueue_in_chain<R: tl::RemoteCall>(
+        &self,
+        request: &R,
+        chain: RequestChain,
+    ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
+        self.request_tx
+            .read()
+            .unwrap()
+            .enqueue_in_chain(request, Priority::Normal, chain)
+    }
+
+    /// Like [`Connection::invoke`], but for a request previously enqueued with
+    /// [`Connection::enqueue_in_chain`], whose response will be received through `rx`.
+    pub(crate) async fn invoke_in_chain<R: tl::RemoteCall, F: Fn(Vec<tl::enums::Updates>)>(
+        &self,
+        request: &R,
+        flood_sleep_threshold: u32,
+        timeout: Option<Duration>,
+        chain: RequestChain,
+        rx: oneshot::Receiver<Result<Vec<u8>, InvocationError>>,
+        on_updates: F,
+    ) -> Result<R::Return, InvocationError> {
+        let invoke = self.invoke_until_done(
+            request,
+            flood_sleep_threshold,
+            Priority::Normal,
+            Some(chain),
+            Some(rx),
+            on_updates,
+        );
+        Self::with_timeout::<R>(invoke, timeout).await
+    }
+
+    async fn with_timeout<R: tl::RemoteCall>(
+        invoke: impl Future<Output = Result<R::Return, InvocationError>>,
+        timeout: Option<Duration>,
+    ) -> Result<R::Return, InvocationError> {
+        let Some(timeout) = timeout else {
+            return invoke.await;
+        };
+
+        // Dropping the invocation future drops the receiver, which cancels the request.
+        let invoke = pin!(invoke);
+        match select(invoke, pin!(sleep(timeout))).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => {
+                info!(
+                    "cancelling {} after timing out ({:?})",
+                    std::any::type_name::<R>(),
+                    timeout
+                );
+                Err(InvocationError::Timeout)
+            }
+        }
+    }
+
+    async fn invoke_until_done<R: tl::RemoteCall, F: Fn(Vec<tl::enums::Updates>)>(
+        &self,
+        request: &R,
+        flood_sleep_threshold: u32,
+        priority: Priority,
+        chain: Option<RequestChain>,
+        rx: Option<oneshot::Receiver<Result<Vec<u8>, InvocationError>>>,
         on_updates: F,
     ) -> Result<R::Return, InvocationError> {
         let mut slept_flood = false;
 
-        let mut rx = { self.request_tx.read().unwrap().enqueue(request) };
+        let mut rx = match rx {
+            Some(rx) => rx,
+            None => self.enqueue(request, priority, chain, &on_updates).await?,
+        };
         loop {
             match rx.try_recv() {
                 Ok(response) => match response {
@@ -450,7 +906,7 @@ impl Connection {
                         );
                         sleep(delay).await;
                         slept_flood = true;
-                        rx = self.request_tx.read().unwrap().enqueue(request);
+                        rx = self.enqueue(request, priority, chain, &on_updates).await?;
                         continue;
                     }
                     Err(e) => break Err(e),
@@ -465,6 +921,36 @@ impl Connection {
         }
     }
 
+    /// Enqueue the request, waiting for network events until there is room for it if the
+    /// request queue is full.
+    async fn enqueue<R: tl::RemoteCall, F: Fn(Vec<tl::enums::Updates>)>(
+        &self,
+        request: &R,
+        priority: Priority,
+        chain: Option<RequestChain>,
+        on_updates: &F,
+    ) -> Result<oneshot::Receiver<Result<Vec<u8>, InvocationError>>, InvocationError> {
+        loop {
+            let result = {
+                let request_tx = self.request_tx.read().unwrap();
+                match chain {
+                    Some(chain) => request_tx.try_enqueue_in_chain(request, priority, chain),
+                    None => request_tx.try_enqueue_with_priority(request, priority),
+                }
+            };
+            match result {
+                Ok(rx) => break Ok(rx),
+                Err(QueueFull) => {
+                    debug!(
+                        "request queue is full; waiting before enqueueing {}",
+                        std::any::type_name::<R>()
+                    );
+                    on_updates(self.step().await?);
+                }
+            }
+        }
+    }
+
     async fn step(&self) -> Result<Vec<tl::enums::Updates>, sender::ReadError> {
         let ticket_number = self.step_counter.load(Ordering::SeqCst);
         let mut sender = self.sender.lock().await;
@@ -480,3 +966,51 @@ impl Connection {
         }
     }
 }
+
+#[cfg(all(test, not(all(target_arch = "wasm32", target_os = "unknown"))))]
+mod tests {
+    use super::*;
+    use grammers_mtsender::NoReconnect;
+    use tokio::net::TcpListener;
+    use tokio::runtime;
+
+    #[test]
+    fn invoke_times_out_and_cancels_request() {
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            // The server accepts the connection but never responds.
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let (sender, enqueuer) = sender::connect_with_auth(
+                Transport::Full(transport::Full::new()),
+                ServerAddr::Tcp {
+                    address: listener.local_addr().unwrap(),
+                },
+                [0; 256],
+                &NoReconnect,
+            )
+            .await
+            .unwrap();
+            let conn = Connection::new(sender, enqueuer);
+
+            let result = conn
+                .invoke(
+                    &tl::functions::Ping { ping_id: 1 },
+                    0,
+                    Some(Duration::from_millis(100)),
+                    Priority::Normal,
+                    None,
+                    |_| {},
+                )
+                .await;
+            assert!(matches!(result, Err(InvocationError::Timeout)));
+            assert_eq!(conn.stats.read().unwrap().snapshot().in_flight, 1);
+
+            // The next step notices the request was cancelled and stops waiting for it.
+            conn.step().await.unwrap();
+            assert_eq!(conn.stats.read().unwrap().snapshot().in_flight, 0);
+        });
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Methods related to polls and quizzes.
+
+use super::Client;
+use crate::types::{IterBuffer, Media, Poll, PollVote};
+use crate::utils::bad_request;
+use crate::{ChatMap, utils};
+pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+
+const MAX_VOTES_LIMIT: usize = 50;
+
+pub type PollVoteIter = IterBuffer<tl::functions::messages::GetPollVotes, PollVote>;
+
+impl PollVoteIter {
+    fn new(client: &Client, chat: PackedChat, message_id: i32, option: Option<Vec<u8>>) -> Self {
+        Self::from_request(
+            client,
+            MAX_VOTES_LIMIT,
+            tl::functions::messages::GetPollVotes {
+                peer: chat.to_input_peer(),
+                id: message_id,
+                option,
+                offset: None,
+                limit: 0,
+            },
+        )
+    }
+
+    /// Determines how many votes there are in total.
+    ///
+    /// This only performs a network call if `next` has not been called before.
+    pub async fn total(&mut self) -> Result<usize, InvocationError> {
+        if let Some(total) = self.total {
+            return Ok(total);
+        }
+
+        self.request.limit = 1;
+        let tl::enums::messages::VotesList::List(votes) = self.client.invoke(&self.request).await?;
+        let total = votes.count as usize;
+        self.total = Some(total);
+        Ok(total)
+    }
+
+    /// Return the next `PollVote` from the internal buffer, filling the buffer previously if
+    /// it's empty.
+    ///
+    /// Returns `None` if the `limit` is reached or there are no votes left.
+    pub async fn next(&mut self) -> Result<Option<PollVote>, InvocationError> {
+        if let Some(result) = self.next_raw() {
+            return result;
+        }
+
+        self.request.limit = self.determine_limit(MAX_VOTES_LIMIT);
+        let tl::enums::messages::VotesList::List(votes) = self.client.invoke(&self.request).await?;
+
+        {
+            let mut state = self.client.0.state.write().unwrap();
+            // Telegram can return peers without hash (e.g. Users with 'min: true')
+            let _ = state.chat_hashes.extend(&votes.users, &votes.chats);
+        }
+
+        self.total = Some(votes.count as usize);
+        self.request.offset = votes.next_offset;
+        if self.request.offset.is_none() {
+            self.last_chunk = true;
+        }
+
+        let chats = ChatMap::new(votes.users, votes.chats);
+        self.buffer.extend(votes.votes.into_iter().map(|vote| {
+            let chat = utils::always_find_entity(&vote.peer(), &chats, &self.client);
+            PollVote::new(vote, chat)
+        }));
+
+        Ok(self.pop_item())
+    }
+}
+
+impl Client {
+    /// Vote in the poll found in the message with the given identifier.
+    ///
+    /// The options of the answers can be found in [`Poll::iter_answers`]. More than one option
+    /// may only be given if the poll is multiple choice, and no options at all retracts the vote.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::types::Media;
+    ///
+    /// let message_id = 123;
+    /// let message = client.get_messages_by_id(&chat, &[message_id]).await?.remove(0);
+    ///
+    /// if let Some(Media::Poll(poll)) = message.and_then(|message| message.media()) {
+    ///     if let Some(answer) = poll.iter_answers().next() {
+    ///         client.vote_poll(&chat, message_id, vec![answer.option.clone()]).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn vote_poll<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+        options: Vec<Vec<u8>>,
+    ) -> Result<(), InvocationError> {
+        self.invoke(&tl::functions::messages::SendVote {
+            peer: chat.into().to_input_peer(),
+            msg_id: message_id,
+            options,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Close the poll found in the message with the given identifier, so that no more votes
+    /// can be cast.
+    ///
+    /// Only the sender of the poll can close it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    ///
+    /// client.close_poll(&chat, message_id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn close_poll<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+    ) -> Result<(), InvocationError> {
+        let chat = chat.into();
+        let mut poll = self
+            .get_message_poll(chat, message_id)
+            .await?
+            .ok_or_else(|| bad_request("MSG_ID_INVALID"))?;
+        poll.raw.closed = true;
+
+        self.invoke(&tl::functions::messages::EditMessage {
+            no_webpage: false,
+            invert_media: false,
+            peer: chat.to_input_peer(),
+            id: message_id,
+            message: None,
+            media: Some(
+                tl::types::InputMediaPoll {
+                    poll: poll.raw.into(),
+                    correct_answers: None,
+                    solution: None,
+                    solution_entities: None,
+                }
+                .into(),
+            ),
+            reply_markup: None,
+            entities: None,
+            schedule_date: None,
+            quick_reply_shortcut_id: None,
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Fetch the most recent results of the poll found in the message with the given identifier.
+    ///
+    /// Returns `None` if the message does not exist or it does not contain a poll.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    ///
+    /// if let Some(poll) = client.get_poll_results(&chat, message_id).await? {
+    ///     println!("{:?} people voted", poll.total_voters());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_poll_results<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+    ) -> Result<Option<Poll>, InvocationError> {
+        let chat = chat.into();
+        let updates = self
+            .invoke(&tl::functions::messages::GetPollResults {
+                peer: chat.to_input_peer(),
+                msg_id: message_id,
+            })
+            .await?;
+
+        let updates = match updates {
+            tl::enums::Updates::UpdateShort(update) => vec![update.update],
+            tl::enums::Updates::Combined(updates) => updates.updates,
+            tl::enums::Updates::Updates(updates) => updates.updates,
+            _ => Vec::new(),
+        };
+        let Some(update) = updates.into_iter().find_map(|update| match update {
+            tl::enums::Update::MessagePoll(update) => Some(update),
+            _ => None,
+        }) else {
+            return self.get_message_poll(chat, message_id).await;
+        };
+
+        let tl::enums::PollResults::Results(results) = update.results;
+        Ok(match update.poll {
+            Some(tl::enums::Poll::Poll(raw)) => Some(Poll {
+                raw,
+                raw_results: results,
+            }),
+            // The poll itself only changes when it's closed, so it's often left out.
+            None => self
+                .get_message_poll(chat, message_id)
+                .await?
+                .map(|poll| Poll {
+                    raw: poll.raw,
+                    raw_results: results,
+                }),
+        })
+    }
+
+    /// Iterate over the votes cast in the poll found in the message with the given identifier,
+    /// optionally only those for the answer with the given option.
+    ///
+    /// Only polls with public voters can be inspected. Votes in a quiz can only be seen once
+    /// the logged-in account has answered it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let message_id = 123;
+    /// let mut votes = client.iter_poll_voters(&chat, message_id, None);
+    ///
+    /// while let Some(vote) = votes.next().await? {
+    ///     println!("{} voted {:?}", vote.chat().id(), vote.options());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_poll_voters<C: Into<PackedChat>>(
+        &self,
+        chat: C,
+        message_id: i32,
+        option: Option<Vec<u8>>,
+    ) -> PollVoteIter {
+        PollVoteIter::new(self, chat.into(), message_id, option)
+    }
+
+    async fn get_message_poll(
+        &self,
+        chat: PackedChat,
+        message_id: i32,
+    ) -> Result<Option<Poll>, InvocationError> {
+        Ok(self
+            .get_messages_by_id(chat, &[message_id])
+            .await?
+            .pop()
+            .flatten()
+            .and_then(|message| message.media())
+            .and_then(|media| match media {
+                Media::Poll(poll) => Some(poll),
+                _ => None,
+            }))
+    }
+}
@@ -0,0 +1,541 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Management of several accounts from the same process.
+
+use super::net::DcOptions;
+use super::{Client, Config, InitParams};
+use crate::types::Update;
+use futures_util::future::{Either, join_all, select, select_all};
+use grammers_mtsender::ConnectionStats;
+pub use grammers_mtsender::{AuthorizationError, InvocationError};
+use grammers_session::Session;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, Notify};
+
+/// The connection status of an account in a [`ClientPool`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AccountStatus {
+    /// The account has not been connected yet, because it was not used.
+    Idle,
+    /// A connection to Telegram is being established.
+    Connecting,
+    /// The account is connected, and its updates are being received.
+    Connected,
+    /// The last connection attempt failed, or the connection was lost. The account will be
+    /// connected again the next time it's used.
+    Failed,
+}
+
+/// A snapshot of the health of an account in a [`ClientPool`].
+#[derive(Clone, Debug)]
+pub struct AccountHealth {
+    /// The connection status of the account.
+    pub status: AccountStatus,
+    /// The last error that caused the account to fail, if any.
+    pub last_error: Option<String>,
+    /// The statistics of the connection, if the account is connected.
+    pub stats: Option<ConnectionStats>,
+}
+
+/// A manager for several accounts running in the same process.
+///
+/// Each account is identified by a name of your choice, and is only connected once it's first
+/// used (or when [`ClientPool::connect_all`] is called). All the clients in the pool share what
+/// they learn about the addresses of Telegram's datacenters, and their updates are merged into
+/// a single stream, tagged with the account they belong to.
+///
+/// The pool does not spawn any tasks. Updates are only fetched while [`ClientPool::next_update`]
+/// is being awaited, so it works with any executor.
+///
+/// Per-account rate limiting can be configured through [`InitParams::rate_limit`].
+///
+/// The sessions are owned by the pool. Use [`ClientPool::save_session`] to persist them.
+pub struct ClientPool {
+    api_id: i32,
+    api_hash: String,
+    params: InitParams,
+    dc_options: Arc<DcOptions>,
+    accounts: Mutex<HashMap<String, Arc<Account>>>,
+    // Notified when the set of connected accounts changes, so that the updates are fetched from
+    // the right clients.
+    accounts_changed: Notify,
+    // Held while fetching updates. Contains the index of the account to poll first next time, so
+    // that a busy account cannot starve the rest.
+    next_update_start: AsyncMutex<usize>,
+}
+
+struct Account {
+    params: InitParams,
+    state: Mutex<AccountState>,
+    // Held while connecting, so that concurrent uses only connect once.
+    connect_lock: AsyncMutex<()>,
+}
+
+struct AccountState {
+    status: AccountStatus,
+    last_error: Option<String>,
+    // Present until the account connects, at which point the client owns it.
+    session: Option<Session>,
+    client: Option<Client>,
+}
+
+impl ClientPool {
+    /// Create a new empty pool. The given parameters will be used by the accounts added with
+    /// [`ClientPool::add_account`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grammers_client::ClientPool;
+    ///
+    /// // Note: these are example values and are not actually valid.
+    /// //       Obtain your own with the developer's phone at https://my.telegram.org.
+    /// const API_ID: i32 = 932939;
+    /// const API_HASH: &str = "514727c32270b9eb8cc16daf17e21e57";
+    ///
+    /// let pool = ClientPool::new(API_ID, API_HASH.to_string(), Default::default());
+    /// ```
+    pub fn new(api_id: i32, api_hash: String, params: InitParams) -> Self {
+        Self {
+            api_id,
+            api_hash,
+            params,
+            dc_options: Arc::default(),
+            accounts: Mutex::new(HashMap::new()),
+            accounts_changed: Notify::new(),
+            next_update_start: AsyncMutex::new(0),
+        }
+    }
+
+    /// Add an account to the pool under the given name, without connecting it yet.
+    ///
+    /// If an account with the same name already existed, it is replaced and disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn f(pool: grammers_client::ClientPool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_session::Session;
+    ///
+    /// for name in ["alice", "bob"] {
+    ///     let session = Session::load_file_or_create(format!("{name}.session"))?;
+    ///     pool.add_account(name, session);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_account<S: Into<String>>(&self, name: S, session: Session) {
+        self.add_account_with_params(name, session, self.params.clone());
+    }
+
+    /// Like [`ClientPool::add_account`], but using different parameters for this account.
+    pub fn add_account_with_params<S: Into<String>>(
+        &self,
+        name: S,
+        session: Session,
+        params: InitParams,
+    ) {
+        let account = Arc::new(Account {
+            params,
+            state: Mutex::new(AccountState {
+                status: AccountStatus::Idle,
+                last_error: None,
+                session: Some(session),
+                client: None,
+            }),
+            connect_lock: AsyncMutex::new(()),
+        });
+        if self
+            .accounts
+            .lock()
+            .unwrap()
+            .insert(name.into(), account)
+            .is_some()
+        {
+            self.accounts_changed.notify_waiters();
+        }
+    }
+
+    /// Remove the account with the given name from the pool, disconnecting it.
+    ///
+    /// Returns `false` if there was no such account.
+    pub fn remove_account(&self, name: &str) -> bool {
+        let removed = self.accounts.lock().unwrap().remove(name).is_some();
+        if removed {
+            self.accounts_changed.notify_waiters();
+        }
+        removed
+    }
+
+    /// The names of all the accounts in the pool, in no particular order.
+    pub fn accounts(&self) -> Vec<String> {
+        self.accounts.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Get the client for the account with the given name, connecting it first if needed.
+    ///
+    /// Returns `None` if there is no such account. Once connected, the updates received by the
+    /// account become available through [`ClientPool::next_update`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(pool: grammers_client::ClientPool) -> Result<(), Box<dyn std::error::Error>> {
+    /// if let Some(client) = pool.client("alice").await? {
+    ///     println!("alice is {}", client.get_me().await?.full_name());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn client(&self, name: &str) -> Result<Option<Client>, AuthorizationError> {
+        let Some(account) = self.account(name) else {
+            return Ok(None);
+        };
+        if let Some(client) = account.connected_client() {
+            return Ok(Some(client));
+        }
+
+        let _guard = account.connect_lock.lock().await;
+        if let Some(client) = account.connected_client() {
+            return Ok(Some(client));
+        }
+
+        let session = {
+            let mut state = account.state.lock().unwrap();
+            state.status = AccountStatus::Connecting;
+            let data = state
+                .session
+                .as_ref()
+                .expect("session should be present while disconnected")
+                .save();
+            Session::load(&data).expect("session should be loadable after being saved")
+        };
+
+        info!("connecting account {} in pool", name);
+        let result = Client::connect_with_dc_options(
+            Config {
+                session,
+                api_id: self.api_id,
+                api_hash: self.api_hash.clone(),
+                params: account.params.clone(),
+            },
+            Arc::clone(&self.dc_options),
+        )
+        .await;
+
+        let mut state = account.state.lock().unwrap();
+        match result {
+            Ok(client) => {
+                state.status = AccountStatus::Connected;
+                state.session = None;
+                state.client = Some(client.clone());
+                self.accounts_changed.notify_waiters();
+                Ok(Some(client))
+            }
+            Err(e) => {
+                warn!("failed to connect account {} in pool: {}", name, e);
+                state.status = AccountStatus::Failed;
+                state.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Connect all the accounts in the pool that are not connected yet, concurrently.
+    ///
+    /// Returns the name and error of every account that failed to connect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(pool: grammers_client::ClientPool) -> Result<(), Box<dyn std::error::Error>> {
+    /// for (name, error) in pool.connect_all().await {
+    ///     eprintln!("account {name} failed to connect: {error}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_all(&self) -> Vec<(String, AuthorizationError)> {
+        let names = self.accounts();
+        let results = join_all(names.iter().map(|name| self.client(name))).await;
+        names
+            .into_iter()
+            .zip(results)
+            .filter_map(|(name, result)| result.err().map(|e| (name, e)))
+            .collect()
+    }
+
+    /// Returns the next update received by any of the connected accounts, along with the name
+    /// of the account that received it.
+    ///
+    /// Errors are also tagged with the account that caused them. An account whose updates fail
+    /// is marked as [failed](AccountStatus::Failed), but the rest continue to work.
+    ///
+    /// Updates are only fetched while this method is being awaited, in the same way as with
+    /// [`Client::next_update`]. If no account is connected, this will wait until one is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(pool: grammers_client::ClientPool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::Update;
+    ///
+    /// pool.connect_all().await;
+    /// loop {
+    ///     match pool.next_update().await {
+    ///         (name, Ok(Update::NewMessage(message))) if !message.outgoing() => {
+    ///             println!("{name} received: {}", message.text());
+    ///         }
+    ///         (name, Err(error)) => eprintln!("{name} failed: {error}"),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_update(&self) -> (String, Result<Update, InvocationError>) {
+        let mut start = self.next_update_start.lock().await;
+        loop {
+            // Registered before looking at the accounts, so that a change in between is not missed.
+            let mut changed = pin!(self.accounts_changed.notified());
+            changed.as_mut().enable();
+
+            let mut connected = self
+                .accounts
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|(name, account)| {
+                    let client = account.connected_client()?;
+                    Some((name.clone(), Arc::clone(account), client))
+                })
+                .collect::<Vec<_>>();
+            if connected.is_empty() {
+                changed.await;
+                continue;
+            }
+
+            // Sorted so that the rotation is stable across calls.
+            connected.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+            let offset = *start % connected.len();
+            connected.rotate_left(offset);
+
+            let updates = select_all(
+                connected
+                    .iter()
+                    .map(|(_, _, client)| Box::pin(client.next_update())),
+            );
+            match select(changed, updates).await {
+                Either::Left(_) => continue,
+                Either::Right(((result, index, _), _)) => {
+                    let (name, account, _) = &connected[index];
+                    if let Err(e) = &result {
+                        warn!("updates for account {} in pool failed: {}", name, e);
+                        account.fail(e);
+                    }
+                    *start = (offset + index + 1) % connected.len();
+                    return (name.clone(), result);
+                }
+            }
+        }
+    }
+
+    /// Take a snapshot of the health of the account with the given name.
+    ///
+    /// Returns `None` if there is no such account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn f(pool: grammers_client::ClientPool) {
+    /// for name in pool.accounts() {
+    ///     if let Some(health) = pool.health(&name) {
+    ///         println!("{name}: {:?}, rtt {:?}", health.status, health.stats.and_then(|s| s.rtt));
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn health(&self, name: &str) -> Option<AccountHealth> {
+        let account = self.account(name)?;
+        let state = account.state.lock().unwrap();
+        Some(AccountHealth {
+            status: state.status,
+            last_error: state.last_error.clone(),
+            stats: state.client.as_ref().map(Client::stats),
+        })
+    }
+
+    /// Serialize the session of the account with the given name, so that it can be persisted.
+    ///
+    /// Returns `None` if there is no such account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn f(pool: grammers_client::ClientPool) -> Result<(), Box<dyn std::error::Error>> {
+    /// for name in pool.accounts() {
+    ///     if let Some(data) = pool.save_session(&name) {
+    ///         std::fs::write(format!("{name}.session"), data)?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_session(&self, name: &str) -> Option<Vec<u8>> {
+        let account = self.account(name)?;
+        let state = account.state.lock().unwrap();
+        Some(match (&state.client, &state.session) {
+            (Some(client), _) => {
+                client.sync_update_state();
+                client.session().save()
+            }
+            (None, Some(session)) => session.save(),
+            (None, None) => unreachable!("account should have either a client or a session"),
+        })
+    }
+
+    fn account(&self, name: &str) -> Option<Arc<Account>> {
+        self.accounts.lock().unwrap().get(name).cloned()
+    }
+}
+
+impl Account {
+    fn connected_client(&self) -> Option<Client> {
+        self.state.lock().unwrap().client.clone()
+    }
+
+    /// Mark the account as failed, and take back its session so it can connect again later.
+    fn fail(&self, error: &InvocationError) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(client) = state.client.take() {
+            client.sync_update_state();
+            let data = client.session().save();
+            state.session = Some(Session::load(&data).expect("session should be loadable"));
+        }
+        state.status = AccountStatus::Failed;
+        state.last_error = Some(error.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grammers_mtsender::ServerAddr;
+    use grammers_mtsender::utils::sleep;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime;
+
+    fn pool() -> ClientPool {
+        ClientPool::new(1, String::new(), InitParams::default())
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn accounts_are_added_and_removed() {
+        let pool = pool();
+        pool.add_account("alice", Session::new());
+        pool.add_account("bob", Session::new());
+
+        let mut accounts = pool.accounts();
+        accounts.sort();
+        assert_eq!(accounts, ["alice", "bob"]);
+
+        assert!(pool.remove_account("alice"));
+        assert!(!pool.remove_account("alice"));
+        assert_eq!(pool.accounts(), ["bob"]);
+    }
+
+    #[test]
+    fn accounts_start_idle() {
+        let pool = pool();
+        pool.add_account("alice", Session::new());
+
+        let health = pool.health("alice").unwrap();
+        assert_eq!(health.status, AccountStatus::Idle);
+        assert!(health.last_error.is_none());
+        assert!(health.stats.is_none());
+        assert!(pool.health("bob").is_none());
+    }
+
+    #[test]
+    fn sessions_are_saved_before_connecting() {
+        let pool = pool();
+        let session = Session::new();
+        session.insert_dc_tcp(2, &"127.0.0.1:1".parse().unwrap(), [1; 256]);
+        let expected = session.save();
+        pool.add_account("alice", session);
+
+        assert_eq!(pool.save_session("alice"), Some(expected));
+        assert_eq!(pool.save_session("bob"), None);
+    }
+
+    #[test]
+    fn unknown_accounts_have_no_client() {
+        block_on(async {
+            assert!(pool().client("alice").await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn failed_connections_are_reported() {
+        block_on(async {
+            // Nothing is listening on the address once the listener is dropped.
+            let address = TcpListener::bind("127.0.0.1:0")
+                .await
+                .unwrap()
+                .local_addr()
+                .unwrap();
+
+            let pool = pool();
+            pool.add_account_with_params(
+                "alice",
+                Session::new(),
+                InitParams {
+                    server_addr: Some(ServerAddr::Tcp { address }),
+                    ..Default::default()
+                },
+            );
+
+            assert!(pool.client("alice").await.is_err());
+            let health = pool.health("alice").unwrap();
+            assert_eq!(health.status, AccountStatus::Failed);
+            assert!(health.last_error.is_some());
+            assert!(pool.save_session("alice").is_some());
+
+            assert_eq!(pool.connect_all().await.len(), 1);
+        });
+    }
+
+    #[test]
+    fn next_update_waits_for_connected_accounts() {
+        block_on(async {
+            let pool = pool();
+            pool.add_account("alice", Session::new());
+
+            let next_update = pin!(pool.next_update());
+            let timeout = pin!(sleep(Duration::from_millis(50)));
+            assert!(matches!(
+                select(next_update, timeout).await,
+                Either::Right(_)
+            ));
+        });
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Client-side limiting of how often requests can be made.
+
+use grammers_mtsender::utils::sleep;
+use std::time::Duration;
+use tokio::sync::Mutex as AsyncMutex;
+use web_time::Instant;
+
+/// How many requests a client may invoke over a period of time.
+///
+/// Short bursts of up to `requests` requests are allowed, after which requests are delayed so
+/// that the average rate does not exceed `requests` per `period`. This can be used to stay
+/// away from Telegram's flood limits before hitting them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+}
+
+/// Token bucket used to enforce a [`RateLimit`].
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: AsyncMutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimit {
+    /// Allow up to `requests` requests every `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero or `period` is empty.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        assert!(!period.is_zero(), "rate limit period must not be empty");
+        Self { requests, period }
+    }
+
+    /// How many requests are allowed every [`RateLimit::period`].
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    /// The period of time over which requests are counted.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    fn per_second(&self) -> f64 {
+        self.requests as f64 / self.period.as_secs_f64()
+    }
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            bucket: AsyncMutex::new(Bucket {
+                tokens: limit.requests as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a new request may be made, then run `f` before serving the next waiter.
+    ///
+    /// The bucket stays locked while waiting, so that waiters are served in order.
+    pub(crate) async fn acquire_then<T>(&self, f: impl FnOnce() -> T) -> T {
+        let mut bucket = self.bucket.lock().await;
+        while let Some(delay) = bucket.take(&self.limit, Instant::now()) {
+            sleep(delay).await;
+        }
+        f()
+    }
+}
+
+impl Bucket {
+    /// Take a token from the bucket, or return how long to wait before one is available.
+    fn take(&mut self, limit: &RateLimit, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * limit.per_second()).min(limit.requests as f64);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / limit.per_second(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst() {
+        let limit = RateLimit::new(3, Duration::from_secs(3));
+        let now = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 3.0,
+            last_refill: now,
+        };
+
+        assert_eq!(bucket.take(&limit, now), None);
+        assert_eq!(bucket.take(&limit, now), None);
+        assert_eq!(bucket.take(&limit, now), None);
+        assert_eq!(bucket.take(&limit, now), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let limit = RateLimit::new(2, Duration::from_secs(1));
+        let now = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 0.0,
+            last_refill: now,
+        };
+
+        assert_eq!(bucket.take(&limit, now), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.take(&limit, now + Duration::from_millis(500)), None);
+
+        // The bucket never holds more than the burst size.
+        let later = now + Duration::from_secs(60);
+        assert_eq!(bucket.take(&limit, later), None);
+        assert_eq!(bucket.take(&limit, later), None);
+        assert!(bucket.take(&limit, later).is_some());
+    }
+
+    #[test]
+    fn waiters_run_in_order() {
+        let limiter = RateLimiter::new(RateLimit::new(1, Duration::from_millis(20)));
+        let order = &std::sync::Mutex::new(Vec::new());
+        let wait = |i| limiter.acquire_then(move || order.lock().unwrap().push(i));
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                // Only the first waiter gets a token right away; the rest must sleep for theirs.
+                futures_util::future::join4(wait(1), wait(2), wait(3), wait(4)).await;
+            });
+
+        assert_eq!(*order.lock().unwrap(), [1, 2, 3, 4]);
+    }
+}
@@ -92,7 +92,7 @@ impl Client {
             };
 
             if let Some(request) = get_diff {
-                let response = self.invoke(&request).await?;
+                let response = self.invoke_unlimited(&request).await?;
                 let (updates, users, chats) = {
                     let state = &mut *self.0.state.write().unwrap();
                     state
@@ -104,7 +104,7 @@ impl Client {
             }
 
             if let Some(request) = get_channel_diff {
-                let maybe_response = self.invoke(&request).await;
+                let maybe_response = self.invoke_unlimited(&request).await;
 
                 let response = match maybe_response {
                     Ok(r) => r,
@@ -204,6 +204,11 @@ impl Client {
             let state = &mut *self.0.state.write().unwrap();
 
             for updates in all_updates {
+                if let tl::enums::Updates::UpdateShort(short) = &updates
+                    && matches!(short.update, tl::enums::Update::LoginToken)
+                {
+                    state.login_token_updated = true;
+                }
                 if state
                     .message_box
                     .ensure_known_peer_hashes(&updates, &mut state.chat_hashes)
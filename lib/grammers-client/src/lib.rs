@@ -52,10 +52,16 @@ pub(crate) mod utils;
 #[cfg(all(feature = "fs", target_arch = "wasm32", target_os = "unknown"))]
 compile_error!("The `fs` feature is not supported on wasm32-unknown-unknown.");
 
-pub use client::{Client, Config, InitParams, SignInError};
+pub use client::{
+    AccountHealth, AccountStatus, Client, ClientPool, Config, InitParams, PasswordError,
+    PasswordUpdate, RateLimit, SignInError,
+};
 pub use types::{ChatMap, InputMedia, InputMessage, Update, button, reply_markup};
 
 pub use grammers_mtproto::transport;
-pub use grammers_mtsender::{FixedReconnect, InvocationError, NoReconnect, ReconnectionPolicy};
+pub use grammers_mtsender::{
+    ConnectionStats, FixedReconnect, InvocationError, LatencyHistogram, MethodStats, NoReconnect,
+    Priority, ReconnectionPolicy, RequestChain,
+};
 pub use grammers_session as session;
 pub use grammers_tl_types;
@@ -7,19 +7,12 @@
 // except according to those terms.
 #![cfg(any(feature = "markdown", feature = "html"))]
 
+pub(crate) use crate::utils::telegram_string_len;
 use std::cmp::Ordering;
 use std::fmt::{self, Write as _};
 
 pub const MENTION_URL_PREFIX: &str = "tg://user?id=";
 
-/// The length of a string, according to Telegram.
-///
-/// Telegram considers the length of the string with surrogate pairs.
-pub fn telegram_string_len(string: &str) -> i32 {
-    // https://en.wikipedia.org/wiki/Plane_(Unicode)#Overview
-    string.encode_utf16().count() as i32
-}
-
 /// Updates the length of the latest `MessageEntity` inside the specified vector.
 ///
 /// # Examples
@@ -0,0 +1,107 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_tl_types as tl;
+
+/// An active session where the account is logged in, such as a Telegram application on a
+/// certain device, or this very client.
+#[derive(Clone, Debug)]
+pub struct Authorization {
+    pub raw: tl::types::Authorization,
+}
+
+impl Authorization {
+    pub fn from_raw(authorization: tl::enums::Authorization) -> Self {
+        let tl::enums::Authorization::Authorization(raw) = authorization;
+        Self { raw }
+    }
+
+    /// The identifier of the session, used to [terminate it].
+    ///
+    /// [terminate it]: crate::Client::terminate_authorization
+    pub fn hash(&self) -> i64 {
+        self.raw.hash
+    }
+
+    /// Whether this is the session used by the current client.
+    pub fn is_current(&self) -> bool {
+        self.raw.current
+    }
+
+    /// Whether the session belongs to an official Telegram application.
+    pub fn is_official_app(&self) -> bool {
+        self.raw.official_app
+    }
+
+    /// Whether the session is still waiting for the two-factor authentication password.
+    pub fn is_password_pending(&self) -> bool {
+        self.raw.password_pending
+    }
+
+    /// Whether the session was created recently, and the login has not been confirmed yet from
+    /// a different session.
+    pub fn is_unconfirmed(&self) -> bool {
+        self.raw.unconfirmed
+    }
+
+    /// The model of the device where the session was created.
+    pub fn device_model(&self) -> &str {
+        &self.raw.device_model
+    }
+
+    /// The platform where the session was created.
+    pub fn platform(&self) -> &str {
+        &self.raw.platform
+    }
+
+    /// The version of the operating system where the session was created.
+    pub fn system_version(&self) -> &str {
+        &self.raw.system_version
+    }
+
+    /// The API ID used by the application that created the session.
+    pub fn api_id(&self) -> i32 {
+        self.raw.api_id
+    }
+
+    /// The name of the application that created the session.
+    pub fn app_name(&self) -> &str {
+        &self.raw.app_name
+    }
+
+    /// The version of the application that created the session.
+    pub fn app_version(&self) -> &str {
+        &self.raw.app_version
+    }
+
+    /// When the session was created.
+    pub fn date_created(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date_created)
+    }
+
+    /// When the session was last used.
+    pub fn date_active(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date_active)
+    }
+
+    /// The IP address the session was last used from.
+    pub fn ip(&self) -> &str {
+        &self.raw.ip
+    }
+
+    /// The country the session was last used from, determined from its IP address.
+    pub fn country(&self) -> &str {
+        &self.raw.country
+    }
+
+    /// The region the session was last used from, determined from its IP address.
+    pub fn region(&self) -> &str {
+        &self.raw.region
+    }
+}
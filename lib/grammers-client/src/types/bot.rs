@@ -0,0 +1,274 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+
+/// A command supported by a bot, shown to users when they type `/` in a chat with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BotCommand {
+    /// The text of the command, without the leading slash.
+    pub command: String,
+    /// The description shown next to the command.
+    pub description: String,
+}
+
+/// The users and chats for which a list of [`BotCommand`] applies.
+///
+/// When several scopes apply, the most specific one takes precedence. For example, the commands
+/// for [`BotCommandScope::ChatMember`] are preferred over those for [`BotCommandScope::Chat`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BotCommandScope {
+    /// Used when no other scope applies.
+    Default,
+    /// All private chats with users.
+    AllPrivateChats,
+    /// All groups and supergroups.
+    AllGroupChats,
+    /// All the administrators of groups and supergroups.
+    AllChatAdministrators,
+    /// A specific chat.
+    Chat(PackedChat),
+    /// All the administrators of a specific group or supergroup.
+    ChatAdministrators(PackedChat),
+    /// A specific user in a specific group or supergroup.
+    ChatMember { chat: PackedChat, user: PackedChat },
+}
+
+/// The button shown next to the message box in private chats with a bot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BotMenuButton {
+    /// Use the button set for all users, or the commands list if none was set.
+    Default,
+    /// Open the list of commands.
+    Commands,
+    /// Open the web app found at `url`, with the given text on the button.
+    WebApp { text: String, url: String },
+}
+
+/// The name and descriptions of a bot, which can be different for each language.
+///
+/// When setting the information, fields left as `None` are not changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BotInfo {
+    /// The name of the bot.
+    pub name: Option<String>,
+    /// The short description shown in the profile of the bot.
+    pub about: Option<String>,
+    /// The description shown in empty chats with the bot.
+    pub description: Option<String>,
+}
+
+/// The administrator rights a bot suggests to be granted when it's added to a group or channel.
+///
+/// By default, no rights are suggested.
+///
+/// # Examples
+///
+/// ```
+/// use grammers_client::types::BotAdminRights;
+///
+/// let rights = BotAdminRights::new().delete_messages(true).ban_users(true);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BotAdminRights {
+    pub raw: tl::types::ChatAdminRights,
+}
+
+impl BotCommand {
+    pub fn new<C: Into<String>, D: Into<String>>(command: C, description: D) -> Self {
+        Self {
+            command: command.into(),
+            description: description.into(),
+        }
+    }
+
+    pub(crate) fn from_raw(command: tl::enums::BotCommand) -> Self {
+        let tl::enums::BotCommand::Command(command) = command;
+        Self {
+            command: command.command,
+            description: command.description,
+        }
+    }
+
+    pub(crate) fn to_raw(&self) -> tl::enums::BotCommand {
+        tl::types::BotCommand {
+            command: self.command.clone(),
+            description: self.description.clone(),
+        }
+        .into()
+    }
+}
+
+impl BotCommandScope {
+    pub(crate) fn to_raw(&self) -> tl::enums::BotCommandScope {
+        use tl::enums::BotCommandScope as S;
+        match self {
+            Self::Default => S::Default,
+            Self::AllPrivateChats => S::Users,
+            Self::AllGroupChats => S::Chats,
+            Self::AllChatAdministrators => S::ChatAdmins,
+            Self::Chat(chat) => tl::types::BotCommandScopePeer {
+                peer: chat.to_input_peer(),
+            }
+            .into(),
+            Self::ChatAdministrators(chat) => tl::types::BotCommandScopePeerAdmins {
+                peer: chat.to_input_peer(),
+            }
+            .into(),
+            Self::ChatMember { chat, user } => tl::types::BotCommandScopePeerUser {
+                peer: chat.to_input_peer(),
+                user_id: user.to_input_user_lossy(),
+            }
+            .into(),
+        }
+    }
+}
+
+impl BotMenuButton {
+    pub(crate) fn from_raw(button: tl::enums::BotMenuButton) -> Self {
+        use tl::enums::BotMenuButton as B;
+        match button {
+            B::Default => Self::Default,
+            B::Commands => Self::Commands,
+            B::Button(button) => Self::WebApp {
+                text: button.text,
+                url: button.url,
+            },
+        }
+    }
+
+    pub(crate) fn to_raw(&self) -> tl::enums::BotMenuButton {
+        use tl::enums::BotMenuButton as B;
+        match self {
+            Self::Default => B::Default,
+            Self::Commands => B::Commands,
+            Self::WebApp { text, url } => tl::types::BotMenuButton {
+                text: text.clone(),
+                url: url.clone(),
+            }
+            .into(),
+        }
+    }
+}
+
+impl BotInfo {
+    pub(crate) fn from_raw(info: tl::enums::bots::BotInfo) -> Self {
+        let tl::enums::bots::BotInfo::Info(info) = info;
+        Self {
+            name: Some(info.name),
+            about: Some(info.about),
+            description: Some(info.description),
+        }
+    }
+}
+
+impl BotAdminRights {
+    pub fn new() -> Self {
+        Self {
+            raw: tl::types::ChatAdminRights {
+                change_info: false,
+                post_messages: false,
+                edit_messages: false,
+                delete_messages: false,
+                ban_users: false,
+                invite_users: false,
+                pin_messages: false,
+                add_admins: false,
+                anonymous: false,
+                manage_call: false,
+                other: false,
+                manage_topics: false,
+                post_stories: false,
+                edit_stories: false,
+                delete_stories: false,
+            },
+        }
+    }
+
+    /// Whether the bot should be able to change the title, photo and other settings.
+    pub fn change_info(mut self, val: bool) -> Self {
+        self.raw.change_info = val;
+        self
+    }
+
+    /// Whether the bot should be able to post messages in channels.
+    pub fn post_messages(mut self, val: bool) -> Self {
+        self.raw.post_messages = val;
+        self
+    }
+
+    /// Whether the bot should be able to edit messages of others in channels.
+    pub fn edit_messages(mut self, val: bool) -> Self {
+        self.raw.edit_messages = val;
+        self
+    }
+
+    /// Whether the bot should be able to delete messages of others.
+    pub fn delete_messages(mut self, val: bool) -> Self {
+        self.raw.delete_messages = val;
+        self
+    }
+
+    /// Whether the bot should be able to ban users.
+    pub fn ban_users(mut self, val: bool) -> Self {
+        self.raw.ban_users = val;
+        self
+    }
+
+    /// Whether the bot should be able to invite users.
+    pub fn invite_users(mut self, val: bool) -> Self {
+        self.raw.invite_users = val;
+        self
+    }
+
+    /// Whether the bot should be able to pin messages.
+    pub fn pin_messages(mut self, val: bool) -> Self {
+        self.raw.pin_messages = val;
+        self
+    }
+
+    /// Whether the bot should be able to promote other users to administrators.
+    pub fn add_admins(mut self, val: bool) -> Self {
+        self.raw.add_admins = val;
+        self
+    }
+
+    /// Whether the bot should remain anonymous in groups.
+    pub fn anonymous(mut self, val: bool) -> Self {
+        self.raw.anonymous = val;
+        self
+    }
+
+    /// Whether the bot should be able to manage voice chats.
+    pub fn manage_call(mut self, val: bool) -> Self {
+        self.raw.manage_call = val;
+        self
+    }
+
+    /// Whether the bot should be able to manage forum topics.
+    pub fn manage_topics(mut self, val: bool) -> Self {
+        self.raw.manage_topics = val;
+        self
+    }
+
+    /// Whether the bot should be able to post, edit and delete stories.
+    pub fn manage_stories(mut self, val: bool) -> Self {
+        self.raw.post_stories = val;
+        self.raw.edit_stories = val;
+        self.raw.delete_stories = val;
+        self
+    }
+}
+
+impl Default for BotAdminRights {
+    fn default() -> Self {
+        Self::new()
+    }
+}
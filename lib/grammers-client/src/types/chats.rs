@@ -6,7 +6,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 use crate::Client;
-use crate::types::Role;
+use crate::types::{Chat, Role};
 use grammers_mtsender::{InvocationError, RpcError};
 use grammers_session::PackedChat;
 use grammers_tl_types as tl;
@@ -21,6 +21,17 @@ use std::{
 };
 use web_time::{SystemTime, UNIX_EPOCH};
 
+/// The kind of channel to create with [`Client::create_channel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelKind {
+    /// A broadcast channel, where only administrators can post messages.
+    Broadcast,
+    /// A supergroup (also known as "megagroup"), where every participant can post messages.
+    Megagroup,
+    /// A supergroup with its messages organized in forum topics.
+    Forum,
+}
+
 type BuilderRes = Result<(), InvocationError>;
 type AdminFutGen<F> = fn(AdminRightsBuilderInner) -> F;
 
@@ -304,6 +315,7 @@ type BannedFutGen<F> = fn(BannedRightsBuilderInner) -> F;
 pub(crate) struct BannedRightsBuilderInner {
     client: Client,
     chat: PackedChat,
+    // Empty when editing the default rights of the chat.
     peer: tl::enums::InputPeer,
     user: tl::enums::InputUser,
     rights: tl::types::ChatBannedRights,
@@ -348,15 +360,31 @@ impl BannedRightsBuilderInner {
             }))
         }
     }
+
+    // Perform the call to edit the default rights of the chat.
+    pub(crate) async fn invoke_default(self) -> Result<(), InvocationError> {
+        self.client
+            .invoke(&tl::functions::messages::EditChatDefaultBannedRights {
+                peer: self.chat.to_input_peer(),
+                banned_rights: tl::enums::ChatBannedRights::Rights(self.rights),
+            })
+            .await
+            .map(drop)
+    }
 }
 
 pin_project! {
-    /// Builder for editing the rights of a non-admin user in a specific chat.
+    /// Builder for editing the rights of a non-admin user in a specific chat, or the default
+    /// rights of all the participants of the chat.
     ///
     /// Certain groups (small group chats) only allow banning (disallow `view_messages`). Trying to
     /// disallow other permissions in these groups will fail.
     ///
-    /// Use [`Client::set_banned_rights`] to retrieve an instance of this type.
+    /// When editing the default rights, `view_messages` and the duration of the restrictions are
+    /// ignored.
+    ///
+    /// Use [`Client::set_banned_rights`] or [`Client::set_default_banned_rights`] to retrieve an
+    /// instance of this type.
     #[must_use = "futures do nothing unless you `.await` or poll them"]
     pub struct BannedRightsBuilder<F: Future<Output = BuilderRes>> {
         inner: Option<BannedRightsBuilderInner>,
@@ -385,15 +413,19 @@ impl<F: Future<Output = BuilderRes>> BannedRightsBuilder<F> {
     pub(crate) fn new(
         client: Client,
         chat: PackedChat,
-        user: PackedChat,
+        user: Option<PackedChat>,
         fut_gen: BannedFutGen<F>,
     ) -> Self {
         Self {
             inner: Some(BannedRightsBuilderInner {
                 client,
                 chat,
-                peer: user.to_input_peer(),
-                user: user.to_input_user_lossy(),
+                peer: user
+                    .map(|u| u.to_input_peer())
+                    .unwrap_or(tl::enums::InputPeer::Empty),
+                user: user
+                    .map(|u| u.to_input_user_lossy())
+                    .unwrap_or(tl::enums::InputUser::Empty),
                 rights: tl::types::ChatBannedRights {
                     view_messages: false,
                     send_messages: false,
@@ -430,11 +462,25 @@ impl<F: Future<Output = BuilderRes>> BannedRightsBuilder<F> {
         self.inner.as_mut().unwrap()
     }
 
-    /// Load the current rights of the user. This lets you trivially grant or take away specific
-    /// permissions without changing any of the previous ones.
+    /// Load the current rights of the user (or the current default rights of the chat). This lets
+    /// you trivially grant or take away specific permissions without changing any of the previous
+    /// ones.
     pub async fn load_current(mut self) -> Result<Self, InvocationError> {
         let s = self.inner_mut();
-        if let Some(chan) = s.chat.try_to_input_channel() {
+        if let tl::enums::InputPeer::Empty = s.peer {
+            let rights = match s.client.unpack_chat(s.chat).await? {
+                Chat::Group(group) => match group.raw {
+                    tl::enums::Chat::Chat(chat) => chat.default_banned_rights,
+                    tl::enums::Chat::Channel(channel) => channel.default_banned_rights,
+                    _ => None,
+                },
+                Chat::Channel(channel) => channel.raw.default_banned_rights,
+                Chat::User(_) => None,
+            };
+            if let Some(rights) = rights {
+                s.rights = rights.into();
+            }
+        } else if let Some(chan) = s.chat.try_to_input_channel() {
             let tl::enums::channels::ChannelParticipant::Participant(user) = s
                 .client
                 .invoke(&tl::functions::channels::GetParticipant {
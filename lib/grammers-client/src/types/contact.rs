@@ -0,0 +1,87 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use super::User;
+use grammers_tl_types as tl;
+
+/// A contact to be imported with [`Client::import_contacts`].
+///
+/// [`Client::import_contacts`]: crate::Client::import_contacts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputContact {
+    /// The phone number of the contact, in international format.
+    pub phone: String,
+    /// The first name the contact will be saved with.
+    pub first_name: String,
+    /// The last name the contact will be saved with. May be empty.
+    pub last_name: String,
+}
+
+/// The result of [`Client::import_contacts`].
+///
+/// Contacts are referred to by their index in the list given to import. Contacts whose phone
+/// number is not registered in Telegram are neither imported nor retried.
+///
+/// [`Client::import_contacts`]: crate::Client::import_contacts
+#[derive(Debug)]
+pub struct ImportedContacts {
+    /// The users that were imported, along with the index of the contact they were imported from.
+    pub imported: Vec<(usize, User)>,
+    /// The contacts that could not be imported because of limits, and should be imported again
+    /// later.
+    pub retry: Vec<usize>,
+}
+
+impl InputContact {
+    pub fn new<P: Into<String>, F: Into<String>, L: Into<String>>(
+        phone: P,
+        first_name: F,
+        last_name: L,
+    ) -> Self {
+        Self {
+            phone: phone.into(),
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+        }
+    }
+
+    pub(crate) fn to_raw(&self, client_id: i64) -> tl::enums::InputContact {
+        tl::types::InputPhoneContact {
+            client_id,
+            phone: self.phone.clone(),
+            first_name: self.first_name.clone(),
+            last_name: self.last_name.clone(),
+        }
+        .into()
+    }
+}
+
+impl ImportedContacts {
+    pub(crate) fn from_raw(imported: tl::types::contacts::ImportedContacts) -> Self {
+        let users = imported
+            .users
+            .into_iter()
+            .map(User::from_raw)
+            .collect::<Vec<_>>();
+
+        Self {
+            imported: imported
+                .imported
+                .into_iter()
+                .filter_map(|tl::enums::ImportedContact::Contact(contact)| {
+                    let user = users.iter().find(|u| u.id() == contact.user_id)?;
+                    Some((contact.client_id as usize, user.clone()))
+                })
+                .collect(),
+            retry: imported
+                .retry_contacts
+                .into_iter()
+                .map(|client_id| client_id as usize)
+                .collect(),
+        }
+    }
+}
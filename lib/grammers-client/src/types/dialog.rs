@@ -6,7 +6,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::{Chat, ChatMap, Message, Peer};
+use super::{Chat, ChatMap, Draft, Message, Peer};
 use grammers_tl_types as tl;
 use std::collections::HashMap;
 
@@ -42,4 +42,15 @@ impl Dialog {
     pub fn chat(&self) -> &Chat {
         &self.chat
     }
+
+    /// The message that was being written in this dialog but has not been sent yet, if any.
+    pub fn draft(&self) -> Option<Draft> {
+        match &self.raw {
+            tl::enums::Dialog::Dialog(tl::types::Dialog {
+                draft: Some(draft @ tl::enums::DraftMessage::Message(_)),
+                ..
+            }) => Some(Draft::new(draft.clone(), self.chat.clone(), None)),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,90 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::types::Chat;
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_tl_types as tl;
+
+/// A message that was being written in a chat, but has not been sent yet.
+///
+/// Drafts are synchronized across all the sessions of the account.
+#[derive(Clone, Debug)]
+pub struct Draft {
+    pub raw: tl::enums::DraftMessage,
+    chat: Chat,
+    topic_id: Option<i32>,
+}
+
+impl Draft {
+    pub(crate) fn new(raw: tl::enums::DraftMessage, chat: Chat, topic_id: Option<i32>) -> Self {
+        Self {
+            raw,
+            chat,
+            topic_id,
+        }
+    }
+
+    /// The chat where the draft is.
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    /// The forum topic where the draft is, if it's not "General".
+    pub fn topic_id(&self) -> Option<i32> {
+        self.topic_id
+    }
+
+    /// Whether the draft is empty, which happens when it has been cleared.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.raw, tl::enums::DraftMessage::Empty(_))
+    }
+
+    /// The text of the draft, which will be empty if the draft was cleared.
+    pub fn text(&self) -> &str {
+        match &self.raw {
+            tl::enums::DraftMessage::Message(draft) => &draft.message,
+            tl::enums::DraftMessage::Empty(_) => "",
+        }
+    }
+
+    /// The formatting entities used to format the text of the draft, if any.
+    pub fn fmt_entities(&self) -> Option<&Vec<tl::enums::MessageEntity>> {
+        match &self.raw {
+            tl::enums::DraftMessage::Message(draft) => draft.entities.as_ref(),
+            tl::enums::DraftMessage::Empty(_) => None,
+        }
+    }
+
+    /// When the draft was last edited, if known.
+    pub fn date(&self) -> Option<DateTime<Utc>> {
+        match &self.raw {
+            tl::enums::DraftMessage::Message(draft) => Some(draft.date),
+            tl::enums::DraftMessage::Empty(draft) => draft.date,
+        }
+        .map(utils::date)
+    }
+
+    /// Whether the link preview will be shown once the draft is sent.
+    pub fn link_preview(&self) -> bool {
+        match &self.raw {
+            tl::enums::DraftMessage::Message(draft) => !draft.no_webpage,
+            tl::enums::DraftMessage::Empty(_) => true,
+        }
+    }
+
+    /// If the draft is replying to another message, return the replied message ID.
+    pub fn reply_to_message_id(&self) -> Option<i32> {
+        match &self.raw {
+            tl::enums::DraftMessage::Message(tl::types::DraftMessage {
+                reply_to: Some(tl::enums::InputReplyTo::Message(reply_to)),
+                ..
+            }) => Some(reply_to.reply_to_msg_id),
+            _ => None,
+        }
+    }
+}
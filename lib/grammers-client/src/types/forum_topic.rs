@@ -0,0 +1,130 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_tl_types as tl;
+
+/// A topic in a forum supergroup, where messages are organized in separate threads.
+///
+/// Messages can be posted into a topic with [`InputMessage::topic`].
+///
+/// [`InputMessage::topic`]: crate::InputMessage::topic
+#[derive(Clone, Debug)]
+pub struct ForumTopic {
+    pub raw: tl::types::ForumTopic,
+}
+
+/// The changes to make to a topic with [`Client::edit_forum_topic`].
+///
+/// Only the settings that are set are changed, and the rest are left as they are.
+///
+/// [`Client::edit_forum_topic`]: crate::Client::edit_forum_topic
+#[derive(Clone, Debug, Default)]
+pub struct EditForumTopic {
+    pub(crate) closed: Option<bool>,
+    pub(crate) hidden: Option<bool>,
+    pub(crate) icon_emoji_id: Option<i64>,
+    pub(crate) title: Option<String>,
+}
+
+impl EditForumTopic {
+    /// Start with no changes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Change the title of the topic.
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Change the custom emoji used as the icon of the topic. Use `0` to go back to the default
+    /// icon.
+    pub fn icon_emoji_id(mut self, icon_emoji_id: i64) -> Self {
+        self.icon_emoji_id = Some(icon_emoji_id);
+        self
+    }
+
+    /// Close or reopen the topic. Only administrators can post into closed topics.
+    pub fn closed(mut self, closed: bool) -> Self {
+        self.closed = Some(closed);
+        self
+    }
+
+    /// Hide or show the topic in the list. Only the "General" topic can be hidden.
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = Some(hidden);
+        self
+    }
+}
+
+impl ForumTopic {
+    /// Returns `None` for topics that have been deleted.
+    pub fn from_raw(topic: tl::enums::ForumTopic) -> Option<Self> {
+        match topic {
+            tl::enums::ForumTopic::Topic(raw) => Some(Self { raw }),
+            tl::enums::ForumTopic::Deleted(_) => None,
+        }
+    }
+
+    /// The identifier of the topic, which is also the identifier of the message that created it.
+    pub fn id(&self) -> i32 {
+        self.raw.id
+    }
+
+    /// The title of the topic.
+    pub fn title(&self) -> &str {
+        &self.raw.title
+    }
+
+    /// When the topic was created.
+    pub fn date(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date)
+    }
+
+    /// The RGB color of the default icon of the topic.
+    pub fn icon_color(&self) -> i32 {
+        self.raw.icon_color
+    }
+
+    /// The identifier of the custom emoji used as the icon of the topic, if any.
+    pub fn icon_emoji_id(&self) -> Option<i64> {
+        self.raw.icon_emoji_id
+    }
+
+    /// Whether the topic was created by the logged-in account.
+    pub fn is_my(&self) -> bool {
+        self.raw.my
+    }
+
+    /// Whether the topic is closed, so that only administrators can post into it.
+    pub fn is_closed(&self) -> bool {
+        self.raw.closed
+    }
+
+    /// Whether the topic is hidden. Only the "General" topic can be hidden.
+    pub fn is_hidden(&self) -> bool {
+        self.raw.hidden
+    }
+
+    /// Whether the topic is pinned.
+    pub fn is_pinned(&self) -> bool {
+        self.raw.pinned
+    }
+
+    /// The identifier of the last message posted in the topic.
+    pub fn top_message_id(&self) -> i32 {
+        self.raw.top_message
+    }
+
+    /// How many messages in the topic have not been read yet.
+    pub fn unread_count(&self) -> i32 {
+        self.raw.unread_count
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use grammers_session::PackedChat;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Options to customize how messages are forwarded with
+/// [`Client::forward_messages_with_options`].
+///
+/// By default, messages are forwarded immediately with the name of their original author.
+///
+/// [`Client::forward_messages_with_options`]: crate::Client::forward_messages_with_options
+#[derive(Clone, Debug, Default)]
+pub struct ForwardOptions {
+    pub(crate) background: bool,
+    pub(crate) drop_author: bool,
+    pub(crate) drop_media_captions: bool,
+    pub(crate) noforwards: bool,
+    pub(crate) schedule_date: Option<i32>,
+    pub(crate) send_as: Option<PackedChat>,
+    pub(crate) silent: bool,
+    pub(crate) topic_id: Option<i32>,
+    pub(crate) with_my_score: bool,
+}
+
+impl ForwardOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether to "send the forwarded messages as background messages".
+    ///
+    /// This description is taken from <https://core.telegram.org/method/messages.forwardMessages>.
+    pub fn background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Whether to hide the original author, so that the messages look as if they were sent by
+    /// the logged-in account.
+    pub fn drop_author(mut self, drop_author: bool) -> Self {
+        self.drop_author = drop_author;
+        self
+    }
+
+    /// Whether to remove the captions of forwarded media. This also hides the original author.
+    pub fn drop_media_captions(mut self, drop_media_captions: bool) -> Self {
+        self.drop_media_captions = drop_media_captions;
+        self
+    }
+
+    /// Whether the forwarded messages should be protected, so that they cannot be forwarded or
+    /// saved again.
+    pub fn noforwards(mut self, noforwards: bool) -> Self {
+        self.noforwards = noforwards;
+        self
+    }
+
+    /// If set to a distant enough future time, the messages won't be forwarded immediately,
+    /// and instead they will be scheduled to be automatically forwarded at a later time.
+    ///
+    /// Bot accounts cannot schedule messages.
+    pub fn schedule_date(mut self, schedule_date: Option<SystemTime>) -> Self {
+        self.schedule_date = schedule_date.map(|t| {
+            t.duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i32)
+                .unwrap_or(0)
+        });
+        self
+    }
+
+    /// Forward the messages on behalf of a channel or linked group, rather than the logged-in
+    /// account.
+    ///
+    /// Use [`Client::get_send_as_peers`] to find out which chats can be used in a given chat.
+    ///
+    /// [`Client::get_send_as_peers`]: crate::Client::get_send_as_peers
+    pub fn send_as<C: Into<PackedChat>>(mut self, send_as: Option<C>) -> Self {
+        self.send_as = send_as.map(Into::into);
+        self
+    }
+
+    /// Whether the forwarded messages should notify people or not.
+    pub fn silent(mut self, silent: bool) -> Self {
+        self.silent = silent;
+        self
+    }
+
+    /// The forum topic into which the messages should be forwarded (its `top_msg_id`).
+    pub fn topic(mut self, topic_id: i32) -> Self {
+        self.topic_id = Some(topic_id);
+        self
+    }
+
+    /// Whether to include the score of the logged-in account when forwarding game messages.
+    pub fn with_my_score(mut self, with_my_score: bool) -> Self {
+        self.with_my_score = with_my_score;
+        self
+    }
+}
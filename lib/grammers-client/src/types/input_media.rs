@@ -6,15 +6,19 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 use super::attributes::Attribute;
-use crate::types::{Media, Uploaded};
+use crate::types::{Media, ReplyTo, Uploaded};
+use grammers_session::PackedChat;
 use grammers_tl_types as tl;
 
 /// Construct and send albums.
 #[derive(Default)]
 pub struct InputMedia {
     pub(crate) entities: Vec<tl::enums::MessageEntity>,
-    pub(crate) reply_to: Option<i32>,
+    pub(crate) reply_to: Option<ReplyTo>,
     pub(crate) caption: String,
+    pub(crate) noforwards: bool,
+    pub(crate) send_as: Option<PackedChat>,
+    pub(crate) topic_id: Option<i32>,
     pub(crate) media: Option<tl::enums::InputMedia>,
     media_ttl: Option<i32>,
     mime_type: Option<String>,
@@ -33,10 +37,42 @@ impl InputMedia {
     ///
     /// Only the reply_to from the first media is used.
     pub fn reply_to(mut self, reply_to: Option<i32>) -> Self {
+        self.reply_to = reply_to.map(ReplyTo::message);
+        self
+    }
+
+    /// What this album should reply to, if anything.
+    ///
+    /// Only the reply header from the first media is used.
+    pub fn reply_header(mut self, reply_to: Option<ReplyTo>) -> Self {
         self.reply_to = reply_to;
         self
     }
 
+    /// The forum topic into which this album should be posted.
+    ///
+    /// Only the topic from the first media is used.
+    pub fn topic(mut self, topic_id: i32) -> Self {
+        self.topic_id = Some(topic_id);
+        self
+    }
+
+    /// Send the album on behalf of a channel or linked group, rather than the logged-in account.
+    ///
+    /// Only the chat from the first media is used.
+    pub fn send_as<C: Into<PackedChat>>(mut self, send_as: Option<C>) -> Self {
+        self.send_as = send_as.map(Into::into);
+        self
+    }
+
+    /// Whether the album should be protected, so that it cannot be forwarded or saved.
+    ///
+    /// Only the setting from the first media is used.
+    pub fn noforwards(mut self, noforwards: bool) -> Self {
+        self.noforwards = noforwards;
+        self
+    }
+
     /// Include the uploaded file as a photo in the album.
     ///
     /// The Telegram server will compress the image and convert it to JPEG format if necessary.
@@ -6,7 +6,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 use super::attributes::Attribute;
-use crate::types::{Media, ReplyMarkup, Uploaded};
+use crate::types::{Media, Poll, ReplyMarkup, ReplyTo, Uploaded};
+use grammers_session::PackedChat;
 use grammers_tl_types as tl;
 use web_time::{SystemTime, UNIX_EPOCH};
 
@@ -16,22 +17,37 @@ const SCHEDULE_ONCE_ONLINE: i32 = 0x7ffffffe;
 /// Construct and send rich text messages with various options.
 #[derive(Clone, Default)]
 pub struct InputMessage {
+    pub(crate) allow_paid_floodskip: bool,
     pub(crate) background: bool,
     pub(crate) clear_draft: bool,
+    pub(crate) effect: Option<i64>,
     pub(crate) entities: Vec<tl::enums::MessageEntity>,
     pub(crate) invert_media: bool,
     pub(crate) link_preview: bool,
+    pub(crate) noforwards: bool,
+    pub(crate) quick_reply_shortcut: Option<tl::enums::InputQuickReplyShortcut>,
     pub(crate) reply_markup: Option<tl::enums::ReplyMarkup>,
-    pub(crate) reply_to: Option<i32>,
+    pub(crate) reply_to: Option<ReplyTo>,
     pub(crate) schedule_date: Option<i32>,
+    pub(crate) send_as: Option<PackedChat>,
     pub(crate) silent: bool,
     pub(crate) text: String,
+    pub(crate) topic_id: Option<i32>,
     pub(crate) media: Option<tl::enums::InputMedia>,
     media_ttl: Option<i32>,
     mime_type: Option<String>,
 }
 
 impl InputMessage {
+    /// Whether the bot is willing to pay Telegram Stars to bypass the flood limits when sending
+    /// messages en masse.
+    ///
+    /// Only bot accounts can make use of this option.
+    pub fn allow_paid_floodskip(mut self, allow_paid_floodskip: bool) -> Self {
+        self.allow_paid_floodskip = allow_paid_floodskip;
+        self
+    }
+
     /// Whether to "send this message as a background message".
     ///
     /// This description is taken from <https://core.telegram.org/method/messages.sendMessage>.
@@ -46,6 +62,15 @@ impl InputMessage {
         self
     }
 
+    /// The identifier of the message effect (such as confetti or hearts) to play when the
+    /// message is opened.
+    ///
+    /// Message effects can only be used in private conversations.
+    pub fn effect(mut self, effect: Option<i64>) -> Self {
+        self.effect = effect;
+        self
+    }
+
     /// The formatting entities within the message (such as bold, italics, etc.).
     pub fn fmt_entities(mut self, entities: Vec<tl::enums::MessageEntity>) -> Self {
         self.entities = entities;
@@ -68,6 +93,27 @@ impl InputMessage {
         self
     }
 
+    /// Whether the message should be protected, so that it cannot be forwarded or saved.
+    pub fn noforwards(mut self, noforwards: bool) -> Self {
+        self.noforwards = noforwards;
+        self
+    }
+
+    /// Add the message to the quick reply shortcut with the given name, instead of sending it to
+    /// the chat.
+    ///
+    /// The shortcut is created if it does not exist yet. Only business accounts can make use of
+    /// quick replies.
+    pub fn quick_reply_shortcut(mut self, shortcut: Option<&str>) -> Self {
+        self.quick_reply_shortcut = shortcut.map(|shortcut| {
+            tl::types::InputQuickReplyShortcut {
+                shortcut: shortcut.to_string(),
+            }
+            .into()
+        });
+        self
+    }
+
     /// Defines the suggested reply markup for the message (such as adding inline buttons).
     /// This will be displayed below the message.
     ///
@@ -87,10 +133,28 @@ impl InputMessage {
     ///
     /// Otherwise, this message will not be a reply to any other.
     pub fn reply_to(mut self, reply_to: Option<i32>) -> Self {
+        self.reply_to = reply_to.map(ReplyTo::message);
+        self
+    }
+
+    /// What this message should reply to, if anything.
+    ///
+    /// Use this instead of [`InputMessage::reply_to`] to reply to messages in other chats,
+    /// quote part of a message, or reply to stories.
+    pub fn reply_header(mut self, reply_to: Option<ReplyTo>) -> Self {
         self.reply_to = reply_to;
         self
     }
 
+    /// The forum topic into which this message should be posted.
+    ///
+    /// If the message is also a reply, the replied message must belong to the same topic.
+    /// Messages are posted into the "General" topic by default.
+    pub fn topic(mut self, topic_id: i32) -> Self {
+        self.topic_id = Some(topic_id);
+        self
+    }
+
     /// If set to a distant enough future time, the message won't be sent immediately,
     /// and instead it will be scheduled to be automatically sent at a later time.
     ///
@@ -117,6 +181,16 @@ impl InputMessage {
         self
     }
 
+    /// Send the message on behalf of a channel or linked group, rather than the logged-in account.
+    ///
+    /// Use [`Client::get_send_as_peers`] to find out which chats can be used in a given chat.
+    ///
+    /// [`Client::get_send_as_peers`]: crate::Client::get_send_as_peers
+    pub fn send_as<C: Into<PackedChat>>(mut self, send_as: Option<C>) -> Self {
+        self.send_as = send_as.map(Into::into);
+        self
+    }
+
     /// Whether the message should notify people or not.
     ///
     /// Defaults to `false`, which means it will notify them. Set it to `true`
@@ -162,6 +236,14 @@ impl InputMessage {
         self
     }
 
+    /// Include a poll or quiz in the message, built with [`Poll::new`].
+    ///
+    /// The text is ignored by Telegram for polls, so it may be empty.
+    pub fn poll(mut self, poll: Poll) -> Self {
+        self.media = Some(poll.to_raw_input_media().into());
+        self
+    }
+
     /// Include the uploaded file as a document in the message.
     ///
     /// You can use this to send videos, stickers, audios, or uncompressed photos.
@@ -5,8 +5,388 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
+use grammers_tl_types as tl;
+use std::time::Duration;
 
+/// How the login code was sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SentCodeType {
+    /// The code was sent as a message to other Telegram applications logged in to the account.
+    App { length: i32 },
+    /// The code was sent via SMS.
+    Sms { length: i32 },
+    /// The code will be dictated in a phone call.
+    Call { length: i32 },
+    /// The code is the phone number the call will come from, which must match the pattern.
+    FlashCall { pattern: String },
+    /// The code is the last `length` digits of the phone number the missed call will come from,
+    /// which begins with the given prefix.
+    MissedCall { prefix: String, length: i32 },
+    /// The code was sent to the login email of the account, which matches the pattern.
+    Email { email_pattern: String, length: i32 },
+    /// A login email must be set up before the code can be sent. See
+    /// [`Client::request_login_email_code`](crate::Client::request_login_email_code).
+    SetUpEmailRequired,
+    /// The code was sent via SMS to an anonymous number, and can be read in the given Fragment URL.
+    FragmentSms { url: String, length: i32 },
+    /// The code was sent via Firebase, which is only available to official applications.
+    FirebaseSms { length: i32 },
+    /// The code is a word sent via SMS, optionally starting with the given prefix.
+    SmsWord { beginning: Option<String> },
+    /// The code is a phrase sent via SMS, optionally starting with the given prefix.
+    SmsPhrase { beginning: Option<String> },
+}
+
+/// How the login code will be sent if it's [requested again].
+///
+/// [requested again]: crate::Client::resend_login_code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodeType {
+    Sms,
+    Call,
+    FlashCall,
+    MissedCall,
+    FragmentSms,
+}
+
+/// The token returned after requesting a login code, needed to complete the login.
+///
+/// It also contains information about how the code was sent.
+#[derive(Clone, Debug)]
 pub struct LoginToken {
     pub(crate) phone: String,
     pub(crate) phone_code_hash: String,
+    pub(crate) code_type: SentCodeType,
+    pub(crate) next_type: Option<CodeType>,
+    pub(crate) timeout: Option<i32>,
+}
+
+/// Settings used when [requesting a login code], which indicate what methods of delivery are
+/// supported by the application.
+///
+/// By default, none of the optional methods are allowed, so the code will typically be sent to
+/// other Telegram applications or via SMS.
+///
+/// [requesting a login code]: crate::Client::request_login_code_with_settings
+#[derive(Clone, Debug, Default)]
+pub struct LoginCodeSettings {
+    pub(crate) allow_app_hash: bool,
+    pub(crate) allow_flashcall: bool,
+    pub(crate) allow_missed_call: bool,
+    pub(crate) current_number: bool,
+}
+
+impl LoginCodeSettings {
+    /// Whether the code can be sent as a flash call, where the code is the number that calls.
+    ///
+    /// The application must be able to intercept the incoming call to check its number.
+    pub fn allow_flashcall(mut self, allow: bool) -> Self {
+        self.allow_flashcall = allow;
+        self
+    }
+
+    /// Whether the code can be sent as a missed call, where the code is part of the number that
+    /// calls.
+    ///
+    /// The application must be able to read the call log to check the number.
+    pub fn allow_missed_call(mut self, allow: bool) -> Self {
+        self.allow_missed_call = allow;
+        self
+    }
+
+    /// Whether the application supports automatically reading the code from an SMS using the
+    /// Android SMS Retriever API.
+    pub fn allow_app_hash(mut self, allow: bool) -> Self {
+        self.allow_app_hash = allow;
+        self
+    }
+
+    /// Whether the phone number the code is requested for is the one in the current device.
+    ///
+    /// This only makes a difference when flash or missed calls are allowed.
+    pub fn current_number(mut self, current: bool) -> Self {
+        self.current_number = current;
+        self
+    }
+
+    pub(crate) fn to_raw(&self) -> tl::enums::CodeSettings {
+        tl::types::CodeSettings {
+            allow_flashcall: self.allow_flashcall,
+            current_number: self.current_number,
+            allow_app_hash: self.allow_app_hash,
+            allow_missed_call: self.allow_missed_call,
+            allow_firebase: false,
+            logout_tokens: None,
+            token: None,
+            app_sandbox: None,
+            unknown_number: false,
+        }
+        .into()
+    }
+}
+
+impl SentCodeType {
+    pub(crate) fn from_raw(code_type: tl::enums::auth::SentCodeType) -> Self {
+        use tl::enums::auth::SentCodeType as T;
+        match code_type {
+            T::App(t) => Self::App { length: t.length },
+            T::Sms(t) => Self::Sms { length: t.length },
+            T::Call(t) => Self::Call { length: t.length },
+            T::FlashCall(t) => Self::FlashCall { pattern: t.pattern },
+            T::MissedCall(t) => Self::MissedCall {
+                prefix: t.prefix,
+                length: t.length,
+            },
+            T::EmailCode(t) => Self::Email {
+                email_pattern: t.email_pattern,
+                length: t.length,
+            },
+            T::SetUpEmailRequired(_) => Self::SetUpEmailRequired,
+            T::FragmentSms(t) => Self::FragmentSms {
+                url: t.url,
+                length: t.length,
+            },
+            T::FirebaseSms(t) => Self::FirebaseSms { length: t.length },
+            T::SmsWord(t) => Self::SmsWord {
+                beginning: t.beginning,
+            },
+            T::SmsPhrase(t) => Self::SmsPhrase {
+                beginning: t.beginning,
+            },
+        }
+    }
+
+    /// The length of the code, if known.
+    pub fn length(&self) -> Option<i32> {
+        match self {
+            Self::App { length }
+            | Self::Sms { length }
+            | Self::Call { length }
+            | Self::MissedCall { length, .. }
+            | Self::Email { length, .. }
+            | Self::FragmentSms { length, .. }
+            | Self::FirebaseSms { length } => Some(*length),
+            Self::FlashCall { .. }
+            | Self::SetUpEmailRequired
+            | Self::SmsWord { .. }
+            | Self::SmsPhrase { .. } => None,
+        }
+    }
+}
+
+impl CodeType {
+    pub(crate) fn from_raw(code_type: tl::enums::auth::CodeType) -> Self {
+        use tl::enums::auth::CodeType as T;
+        match code_type {
+            T::Sms => Self::Sms,
+            T::Call => Self::Call,
+            T::FlashCall => Self::FlashCall,
+            T::MissedCall => Self::MissedCall,
+            T::FragmentSms => Self::FragmentSms,
+        }
+    }
+}
+
+impl LoginToken {
+    pub(crate) fn from_raw(phone: String, sent_code: tl::types::auth::SentCode) -> Self {
+        Self {
+            phone,
+            phone_code_hash: sent_code.phone_code_hash,
+            code_type: SentCodeType::from_raw(sent_code.r#type),
+            next_type: sent_code.next_type.map(CodeType::from_raw),
+            timeout: sent_code.timeout,
+        }
+    }
+
+    /// The phone number the code was requested for.
+    pub fn phone(&self) -> &str {
+        &self.phone
+    }
+
+    /// How the login code was sent.
+    pub fn code_type(&self) -> &SentCodeType {
+        &self.code_type
+    }
+
+    /// The length of the login code, if known.
+    pub fn code_length(&self) -> Option<i32> {
+        self.code_type.length()
+    }
+
+    /// How the code will be sent if it's [requested again], if it can be.
+    ///
+    /// [requested again]: crate::Client::resend_login_code
+    pub fn next_type(&self) -> Option<CodeType> {
+        self.next_type
+    }
+
+    /// How long to wait before the code can be [requested again], if there is such limit.
+    ///
+    /// [requested again]: crate::Client::resend_login_code
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(|t| Duration::from_secs(t.max(0) as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent_code(
+        r#type: tl::enums::auth::SentCodeType,
+        next_type: Option<tl::enums::auth::CodeType>,
+        timeout: Option<i32>,
+    ) -> tl::types::auth::SentCode {
+        tl::types::auth::SentCode {
+            r#type,
+            phone_code_hash: "hash".to_string(),
+            next_type,
+            timeout,
+        }
+    }
+
+    #[test]
+    fn check_from_raw() {
+        let token = LoginToken::from_raw(
+            "+1 415 555 0132".to_string(),
+            sent_code(
+                tl::types::auth::SentCodeTypeSms { length: 5 }.into(),
+                Some(tl::enums::auth::CodeType::Call),
+                Some(60),
+            ),
+        );
+
+        assert_eq!(token.phone(), "+1 415 555 0132");
+        assert_eq!(token.phone_code_hash, "hash");
+        assert_eq!(token.code_type(), &SentCodeType::Sms { length: 5 });
+        assert_eq!(token.code_length(), Some(5));
+        assert_eq!(token.next_type(), Some(CodeType::Call));
+        assert_eq!(token.timeout(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn check_from_raw_without_next() {
+        let token = LoginToken::from_raw(
+            String::new(),
+            sent_code(
+                tl::types::auth::SentCodeTypeFlashCall {
+                    pattern: "+1*".to_string(),
+                }
+                .into(),
+                None,
+                None,
+            ),
+        );
+
+        assert_eq!(
+            token.code_type(),
+            &SentCodeType::FlashCall {
+                pattern: "+1*".to_string()
+            }
+        );
+        assert_eq!(token.code_length(), None);
+        assert_eq!(token.next_type(), None);
+        assert_eq!(token.timeout(), None);
+    }
+
+    #[test]
+    fn check_negative_timeout() {
+        let token = LoginToken::from_raw(
+            String::new(),
+            sent_code(
+                tl::types::auth::SentCodeTypeApp { length: 5 }.into(),
+                None,
+                Some(-1),
+            ),
+        );
+
+        assert_eq!(token.timeout(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn check_code_type_mapping() {
+        use tl::enums::auth::SentCodeType as T;
+
+        let cases: Vec<(T, SentCodeType)> = vec![
+            (
+                tl::types::auth::SentCodeTypeApp { length: 5 }.into(),
+                SentCodeType::App { length: 5 },
+            ),
+            (
+                tl::types::auth::SentCodeTypeCall { length: 6 }.into(),
+                SentCodeType::Call { length: 6 },
+            ),
+            (
+                tl::types::auth::SentCodeTypeMissedCall {
+                    prefix: "+1".to_string(),
+                    length: 4,
+                }
+                .into(),
+                SentCodeType::MissedCall {
+                    prefix: "+1".to_string(),
+                    length: 4,
+                },
+            ),
+            (
+                tl::types::auth::SentCodeTypeEmailCode {
+                    apple_signin_allowed: false,
+                    google_signin_allowed: false,
+                    email_pattern: "a***@example.com".to_string(),
+                    length: 6,
+                    reset_available_period: None,
+                    reset_pending_date: None,
+                }
+                .into(),
+                SentCodeType::Email {
+                    email_pattern: "a***@example.com".to_string(),
+                    length: 6,
+                },
+            ),
+            (
+                tl::types::auth::SentCodeTypeSetUpEmailRequired {
+                    apple_signin_allowed: false,
+                    google_signin_allowed: false,
+                }
+                .into(),
+                SentCodeType::SetUpEmailRequired,
+            ),
+            (
+                tl::types::auth::SentCodeTypeFragmentSms {
+                    url: "https://fragment.com".to_string(),
+                    length: 5,
+                }
+                .into(),
+                SentCodeType::FragmentSms {
+                    url: "https://fragment.com".to_string(),
+                    length: 5,
+                },
+            ),
+            (
+                tl::types::auth::SentCodeTypeSmsWord { beginning: None }.into(),
+                SentCodeType::SmsWord { beginning: None },
+            ),
+            (
+                tl::types::auth::SentCodeTypeSmsPhrase {
+                    beginning: Some("a".to_string()),
+                }
+                .into(),
+                SentCodeType::SmsPhrase {
+                    beginning: Some("a".to_string()),
+                },
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            assert_eq!(SentCodeType::from_raw(raw), expected);
+        }
+
+        use tl::enums::auth::CodeType as C;
+        assert_eq!(CodeType::from_raw(C::Sms), CodeType::Sms);
+        assert_eq!(CodeType::from_raw(C::Call), CodeType::Call);
+        assert_eq!(CodeType::from_raw(C::FlashCall), CodeType::FlashCall);
+        assert_eq!(CodeType::from_raw(C::MissedCall), CodeType::MissedCall);
+        assert_eq!(CodeType::from_raw(C::FragmentSms), CodeType::FragmentSms);
+    }
 }
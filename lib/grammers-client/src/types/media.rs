@@ -9,6 +9,8 @@ use crate::types::photo_sizes::{PhotoSize, VecExt};
 use chrono::{DateTime, Utc};
 use grammers_tl_types as tl;
 use std::fmt::Debug;
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use super::Downloadable;
 
@@ -505,6 +507,165 @@ impl Contact {
 }
 
 impl Poll {
+    /// Start building a new poll with the given question, which can be sent with
+    /// [`InputMessage::poll`].
+    ///
+    /// At least two answers must be added before sending it.
+    ///
+    /// [`InputMessage::poll`]: crate::InputMessage::poll
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn f(chat: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use grammers_client::InputMessage;
+    /// use grammers_client::types::Poll;
+    ///
+    /// let quiz = Poll::new("What is the answer?")
+    ///     .answer("42")
+    ///     .answer("24")
+    ///     .quiz(0)
+    ///     .solution("It has always been 42.", Vec::new());
+    ///
+    /// client.send_message(&chat, InputMessage::text("").poll(quiz)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new<S: Into<String>>(question: S) -> Self {
+        Self {
+            raw: tl::types::Poll {
+                id: 0,
+                closed: false,
+                public_voters: false,
+                multiple_choice: false,
+                quiz: false,
+                question: tl::types::TextWithEntities {
+                    text: question.into(),
+                    entities: Vec::new(),
+                }
+                .into(),
+                answers: Vec::new(),
+                close_period: None,
+                close_date: None,
+            },
+            raw_results: tl::types::PollResults {
+                min: false,
+                results: None,
+                total_voters: None,
+                recent_voters: None,
+                solution: None,
+                solution_entities: None,
+            },
+        }
+    }
+
+    /// The formatting entities used to format the question.
+    pub fn question_entities(mut self, entities: Vec<tl::enums::MessageEntity>) -> Self {
+        let tl::enums::TextWithEntities::Entities(question) = &mut self.raw.question;
+        question.entities = entities;
+        self
+    }
+
+    /// Add an answer option to the poll.
+    pub fn answer<S: Into<String>>(self, text: S) -> Self {
+        self.answer_with_entities(text, Vec::new())
+    }
+
+    /// Add an answer option to the poll, formatted with the given entities.
+    pub fn answer_with_entities<S: Into<String>>(
+        mut self,
+        text: S,
+        entities: Vec<tl::enums::MessageEntity>,
+    ) -> Self {
+        let option = answer_option(self.raw.answers.len());
+        self.raw.answers.push(
+            tl::types::PollAnswer {
+                text: tl::types::TextWithEntities {
+                    text: text.into(),
+                    entities,
+                }
+                .into(),
+                option,
+            }
+            .into(),
+        );
+        self
+    }
+
+    /// Whether more than one answer can be chosen.
+    ///
+    /// Quizzes cannot be multiple choice, so enabling this turns a quiz back into a regular poll.
+    pub fn multiple_choice(mut self, multiple_choice: bool) -> Self {
+        self.raw.multiple_choice = multiple_choice;
+        if multiple_choice {
+            self.raw.quiz = false;
+            self.raw_results.results = None;
+        }
+        self
+    }
+
+    /// Whether the voters can be seen by everyone, rather than the poll being anonymous.
+    pub fn public_voters(mut self, public_voters: bool) -> Self {
+        self.raw.public_voters = public_voters;
+        self
+    }
+
+    /// Turn the poll into a quiz, where the answer at the given index (counting from zero, in
+    /// the order they were added) is the only correct one.
+    ///
+    /// Quizzes cannot be multiple choice, so this also disables [`Poll::multiple_choice`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no answer at the given index, so the answers must be added first.
+    pub fn quiz(mut self, correct_answer: usize) -> Self {
+        assert!(
+            correct_answer < self.raw.answers.len(),
+            "quiz correct answer must be one of the answers added so far"
+        );
+        self.raw.quiz = true;
+        self.raw.multiple_choice = false;
+        self.raw_results.results = Some(vec![
+            tl::types::PollAnswerVoters {
+                chosen: false,
+                correct: true,
+                option: answer_option(correct_answer),
+                voters: 0,
+            }
+            .into(),
+        ]);
+        self
+    }
+
+    /// The explanation shown after answering a quiz incorrectly.
+    pub fn solution<S: Into<String>>(
+        mut self,
+        text: S,
+        entities: Vec<tl::enums::MessageEntity>,
+    ) -> Self {
+        self.raw_results.solution = Some(text.into());
+        self.raw_results.solution_entities = Some(entities);
+        self
+    }
+
+    /// Automatically close the poll after the given amount of time since it was sent.
+    pub fn close_period(mut self, period: Duration) -> Self {
+        self.raw.close_period = Some(period.as_secs() as i32);
+        self.raw.close_date = None;
+        self
+    }
+
+    /// Automatically close the poll at the given point in time.
+    pub fn close_date(mut self, date: SystemTime) -> Self {
+        self.raw.close_date = Some(
+            date.duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i32)
+                .unwrap_or(0),
+        );
+        self.raw.close_period = None;
+        self
+    }
+
     pub fn from_raw_media(poll: tl::types::MessageMediaPoll) -> Self {
         Self {
             raw: match poll.poll {
@@ -519,12 +680,27 @@ impl Poll {
     pub fn to_raw_input_media(&self) -> tl::types::InputMediaPoll {
         tl::types::InputMediaPoll {
             poll: grammers_tl_types::enums::Poll::Poll(self.raw.clone()),
-            correct_answers: None,
-            solution: None,
-            solution_entities: None,
+            // Received quizzes only say which answer is correct once the account has answered.
+            correct_answers: self
+                .iter_voters_summary()
+                .filter(|_| self.raw.quiz)
+                .map(|voters| {
+                    voters
+                        .filter(|voters| voters.correct)
+                        .map(|voters| voters.option.clone())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|correct| !correct.is_empty()),
+            solution: self.raw_results.solution.clone(),
+            solution_entities: self.raw_results.solution_entities.clone(),
         }
     }
 
+    /// The solution of the quiz, shown after answering it incorrectly, if known.
+    pub fn solution_text(&self) -> Option<&str> {
+        self.raw_results.solution.as_deref()
+    }
+
     /// Return question of the poll
     pub fn question(&self) -> &grammers_tl_types::enums::TextWithEntities {
         &self.raw.question
@@ -567,6 +743,11 @@ impl Poll {
     }
 }
 
+/// Answers are identified by their position, as the official clients do.
+fn answer_option(index: usize) -> Vec<u8> {
+    index.to_string().into_bytes()
+}
+
 impl Geo {
     pub fn from_raw_media(geo: tl::types::MessageMediaGeo) -> Option<Self> {
         use tl::enums::GeoPoint as eGeoPoint;
@@ -826,3 +1007,104 @@ impl Downloadable for ChatPhoto {
         Some(self.raw.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer_options(poll: &Poll) -> Vec<&[u8]> {
+        poll.iter_answers()
+            .map(|answer| answer.option.as_slice())
+            .collect()
+    }
+
+    #[test]
+    fn poll_answers_are_numbered() {
+        let poll = Poll::new("Pick one")
+            .answer("a")
+            .answer("b")
+            .answer("c")
+            .public_voters(true);
+
+        assert_eq!(answer_options(&poll), [b"0", b"1", b"2"]);
+        let raw = poll.to_raw_input_media();
+        let tl::enums::Poll::Poll(raw_poll) = raw.poll;
+        assert!(raw_poll.public_voters);
+        assert!(!raw_poll.quiz);
+        assert_eq!(raw.correct_answers, None);
+    }
+
+    #[test]
+    fn quiz_has_correct_answer() {
+        let quiz = Poll::new("Pick one").answer("a").answer("b").quiz(1);
+
+        let raw = quiz.to_raw_input_media();
+        let tl::enums::Poll::Poll(raw_poll) = raw.poll;
+        assert!(raw_poll.quiz);
+        assert_eq!(raw.correct_answers, Some(vec![b"1".to_vec()]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn quiz_needs_existing_answer() {
+        let _ = Poll::new("Pick one").answer("a").quiz(1);
+    }
+
+    #[test]
+    fn quiz_is_not_multiple_choice() {
+        let quiz = Poll::new("Pick some")
+            .answer("a")
+            .answer("b")
+            .multiple_choice(true)
+            .quiz(0);
+        assert!(quiz.is_quiz());
+        assert!(!quiz.raw.multiple_choice);
+
+        let poll = quiz.multiple_choice(true);
+        assert!(!poll.is_quiz());
+        assert!(poll.raw.multiple_choice);
+        assert_eq!(poll.to_raw_input_media().correct_answers, None);
+    }
+
+    #[test]
+    fn received_quiz_without_results() {
+        let mut raw = Poll::new("Pick one").answer("a").answer("b").raw;
+        raw.quiz = true;
+        let results = tl::types::PollResults {
+            min: false,
+            results: Some(vec![
+                tl::types::PollAnswerVoters {
+                    chosen: false,
+                    correct: false,
+                    option: answer_option(0),
+                    voters: 3,
+                }
+                .into(),
+            ]),
+            total_voters: Some(3),
+            recent_voters: None,
+            solution: None,
+            solution_entities: None,
+        };
+
+        let quiz = Poll::from_raw_media(tl::types::MessageMediaPoll {
+            poll: raw.clone().into(),
+            results: results.into(),
+        });
+        assert_eq!(quiz.to_raw_input_media().correct_answers, None);
+
+        let quiz = Poll::from_raw_media(tl::types::MessageMediaPoll {
+            poll: raw.into(),
+            results: tl::types::PollResults {
+                min: false,
+                results: None,
+                total_voters: None,
+                recent_voters: None,
+                solution: None,
+                solution_entities: None,
+            }
+            .into(),
+        });
+        assert_eq!(quiz.to_raw_input_media().correct_answers, None);
+    }
+}
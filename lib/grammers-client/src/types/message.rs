@@ -9,7 +9,8 @@ use crate::ChatMap;
 #[cfg(any(feature = "markdown", feature = "html"))]
 use crate::parsers;
 use crate::types::reactions::InputReactions;
-use crate::types::{InputMessage, Media, Photo};
+use crate::types::reply_to::input_reply_to;
+use crate::types::{InputMessage, Media, Photo, ReplyHeader, ReplyTo};
 use crate::{Client, types};
 use crate::{InputMedia, utils};
 use chrono::{DateTime, Utc};
@@ -87,6 +88,38 @@ pub struct Message {
     pub(crate) chats: Arc<ChatMap>,
 }
 
+/// Convert a peer used as input into the peer Telegram would return for it, if it has one.
+fn input_to_peer(client: &Client, peer: &tl::enums::InputPeer) -> Option<tl::enums::Peer> {
+    use tl::enums::InputPeer as I;
+    Some(match peer {
+        I::Empty => return None,
+        I::PeerSelf => tl::types::PeerUser {
+            user_id: client.0.state.read().unwrap().chat_hashes.self_id(),
+        }
+        .into(),
+        I::Chat(chat) => tl::types::PeerChat {
+            chat_id: chat.chat_id,
+        }
+        .into(),
+        I::User(user) => tl::types::PeerUser {
+            user_id: user.user_id,
+        }
+        .into(),
+        I::UserFromMessage(user) => tl::types::PeerUser {
+            user_id: user.user_id,
+        }
+        .into(),
+        I::Channel(channel) => tl::types::PeerChannel {
+            channel_id: channel.channel_id,
+        }
+        .into(),
+        I::ChannelFromMessage(channel) => tl::types::PeerChannel {
+            channel_id: channel.channel_id,
+        }
+        .into(),
+    })
+}
+
 impl Message {
     pub fn from_raw(
         client: &Client,
@@ -169,7 +202,7 @@ impl Message {
                 legacy: false,
                 edit_hide: false,
                 pinned: false,
-                noforwards: false, // TODO true if channel has noforwads?
+                noforwards: input.noforwards, // TODO also true if channel has noforwads
                 video_processing_pending: false,
                 invert_media: input.invert_media,
                 id: updates.id,
@@ -179,22 +212,28 @@ impl Message {
                 saved_peer_id: None,
                 fwd_from: None,
                 via_bot_id: None,
-                reply_to: input.reply_to.map(|reply_to_msg_id| {
-                    tl::types::MessageReplyHeader {
-                        reply_to_scheduled: false,
-                        forum_topic: false,
-                        quote: false,
-                        reply_to_msg_id: Some(reply_to_msg_id),
-                        reply_to_peer_id: None,
-                        reply_from: None,
-                        reply_media: None,
-                        reply_to_top_id: None,
-                        quote_text: None,
-                        quote_entities: None,
-                        quote_offset: None,
-                    }
-                    .into()
-                }),
+                reply_to: match input_reply_to(input.reply_to.as_ref(), input.topic_id) {
+                    Some(tl::enums::InputReplyTo::Message(reply_to)) => Some(
+                        tl::types::MessageReplyHeader {
+                            reply_to_scheduled: false,
+                            forum_topic: input.topic_id.is_some(),
+                            quote: reply_to.quote_text.is_some(),
+                            reply_to_msg_id: Some(reply_to.reply_to_msg_id),
+                            reply_to_peer_id: reply_to
+                                .reply_to_peer_id
+                                .as_ref()
+                                .and_then(|peer| input_to_peer(client, peer)),
+                            reply_from: None,
+                            reply_media: None,
+                            reply_to_top_id: reply_to.top_msg_id,
+                            quote_text: reply_to.quote_text,
+                            quote_entities: reply_to.quote_entities,
+                            quote_offset: reply_to.quote_offset,
+                        }
+                        .into(),
+                    ),
+                    _ => None,
+                },
                 date: updates.date,
                 message: input.text,
                 media: updates.media,
@@ -328,10 +367,16 @@ impl Message {
         self.raw.via_bot_id
     }
 
-    /// If this message is replying to a previous message, return the header with information
-    /// about that reply.
-    pub fn reply_header(&self) -> Option<tl::enums::MessageReplyHeader> {
-        self.raw.reply_to.clone()
+    /// If this message is replying to a previous message or a story, return the header with
+    /// information about that reply.
+    pub fn reply_header(&self) -> Option<ReplyHeader> {
+        let raw = self.raw.reply_to.clone()?;
+        let peer = match &raw {
+            tl::enums::MessageReplyHeader::Header(header) => header.reply_to_peer_id.as_ref(),
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(header) => Some(&header.peer),
+        };
+        let chat = peer.map(|peer| utils::always_find_entity(peer, &self.chats, &self.client));
+        Some(ReplyHeader::from_raw(raw, chat))
     }
 
     /// The date when this message was produced.
@@ -489,6 +534,13 @@ impl Message {
         }
     }
 
+    /// The reactions of this message, when applicable.
+    pub fn reactions(&self) -> Option<types::MessageReactions> {
+        self.raw.reactions.clone().map(|reactions| {
+            types::MessageReactions::from_raw(reactions, &self.chats, &self.client)
+        })
+    }
+
     /// The date when this message was last edited.
     pub fn edit_date(&self) -> Option<DateTime<Utc>> {
         self.raw.edit_date.map(utils::date)
@@ -530,6 +582,20 @@ impl Message {
         }
     }
 
+    /// If this message was posted in a forum topic other than "General", return the topic ID.
+    pub fn topic_id(&self) -> Option<i32> {
+        if let Some(tl::enums::MessageReplyHeader::Header(m)) = &self.raw.reply_to
+            && m.forum_topic
+        {
+            // Messages directly in the topic "reply" to the message that created it.
+            m.reply_to_top_id.or(m.reply_to_msg_id)
+        } else if let Some(tl::enums::MessageAction::TopicCreate(_)) = &self.raw_action {
+            Some(self.raw.id)
+        } else {
+            None
+        }
+    }
+
     /// Fetch the message that this message is replying to, or `None` if this message is not a
     /// reply to a previous message.
     ///
@@ -582,7 +648,7 @@ impl Message {
         &self,
         mut medias: Vec<InputMedia>,
     ) -> Result<Vec<Option<Self>>, InvocationError> {
-        medias.first_mut().unwrap().reply_to = Some(self.raw.id);
+        medias.first_mut().unwrap().reply_to = Some(ReplyTo::message(self.raw.id));
         self.client.send_album(&self.chat(), medias).await
     }
 
@@ -600,6 +666,18 @@ impl Message {
             .map(|mut msgs| msgs.pop().unwrap().unwrap())
     }
 
+    /// Send a copy of this message to the given chat, without the header saying that it was
+    /// forwarded.
+    ///
+    /// Shorthand for `Client::copy_messages`. To copy an album, pass all of its messages to
+    /// that method instead.
+    pub async fn copy_to<C: Into<PackedChat>>(&self, chat: C) -> Result<Self, InvocationError> {
+        self.client
+            .copy_messages(chat, std::slice::from_ref(self))
+            .await
+            .map(|mut msgs| msgs.pop().unwrap().unwrap())
+    }
+
     /// Edit this message to change its text or media.
     ///
     /// Shorthand for `Client::edit_message`.
@@ -5,6 +5,7 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
+use grammers_tl_types as tl;
 
 /// Occurs whenever a message is deleted.
 /// Note that this event isn’t 100% reliable, since Telegram doesn’t always
@@ -51,3 +52,32 @@ impl MessageDeletion {
         self.messages
     }
 }
+
+/// Occurs whenever scheduled messages are deleted, either because they were
+/// deleted before being sent, or because they were sent.
+#[derive(Debug, Clone)]
+pub struct ScheduledMessageDeletion {
+    pub(crate) raw: tl::types::UpdateDeleteScheduledMessages,
+}
+
+impl ScheduledMessageDeletion {
+    /// Returns the ID of the chat where the scheduled messages were.
+    pub fn chat_id(&self) -> i64 {
+        match &self.raw.peer {
+            tl::enums::Peer::User(user) => user.user_id,
+            tl::enums::Peer::Chat(chat) => chat.chat_id,
+            tl::enums::Peer::Channel(channel) => channel.channel_id,
+        }
+    }
+
+    /// Returns the slice of scheduled message IDs that was deleted.
+    pub fn messages(&self) -> &[i32] {
+        &self.raw.messages
+    }
+
+    /// Returns the IDs of the messages that were sent in place of the deleted scheduled
+    /// messages, if they were deleted because they were sent.
+    pub fn sent_messages(&self) -> Option<&[i32]> {
+        self.raw.sent_messages.as_deref()
+    }
+}
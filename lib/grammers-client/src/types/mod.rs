@@ -12,13 +12,19 @@
 //! they directly uses `grammers-tl-types`. This will probably change before the 1.0 release.
 pub mod action;
 pub mod attributes;
+pub mod authorization;
+pub mod bot;
 pub mod button;
 pub mod callback_query;
 pub mod chat;
 pub mod chat_map;
 pub mod chats;
+pub mod contact;
 pub mod dialog;
 pub mod downloadable;
+pub mod draft;
+pub mod forum_topic;
+pub mod forward_options;
 pub mod inline;
 pub mod input_media;
 pub mod input_message;
@@ -27,38 +33,56 @@ pub mod login_token;
 pub mod media;
 pub mod message;
 pub mod message_deletion;
+pub mod new_password;
 pub mod participant;
 pub mod password_token;
 pub mod permissions;
 pub mod photo_sizes;
+pub mod poll_vote;
+pub mod privacy;
+pub mod qr_login_token;
 pub mod reactions;
 pub mod reply_markup;
+pub mod reply_to;
 pub mod terms_of_service;
 pub mod update;
 
 pub use action::ActionSender;
 pub use attributes::Attribute;
+pub use authorization::Authorization;
+pub use bot::{BotAdminRights, BotCommand, BotCommandScope, BotInfo, BotMenuButton};
 pub use callback_query::CallbackQuery;
 pub use chat::{Channel, Chat, Group, PackedChat, Platform, RestrictionReason, User};
 pub use chat_map::ChatMap;
 pub(crate) use chat_map::Peer;
-pub use chats::{AdminRightsBuilder, BannedRightsBuilder};
+pub use chats::{AdminRightsBuilder, BannedRightsBuilder, ChannelKind};
+pub use contact::{ImportedContacts, InputContact};
 pub use dialog::Dialog;
 pub use downloadable::Downloadable;
+pub use draft::Draft;
+pub use forum_topic::{EditForumTopic, ForumTopic};
+pub use forward_options::ForwardOptions;
 pub use inline::query::InlineQuery;
 pub use inline::send::InlineSend;
 pub use input_media::InputMedia;
 pub use input_message::InputMessage;
 pub use iter_buffer::IterBuffer;
-pub use login_token::LoginToken;
+pub use login_token::{CodeType, LoginCodeSettings, LoginToken, SentCodeType};
 pub(crate) use media::Uploaded;
-pub use media::{ChatPhoto, Media, Photo};
+pub use media::{ChatPhoto, Media, Photo, Poll};
 pub use message::Message;
-pub use message_deletion::MessageDeletion;
+pub use message_deletion::{MessageDeletion, ScheduledMessageDeletion};
+pub use new_password::NewPassword;
 pub use participant::{Participant, Role};
 pub use password_token::PasswordToken;
 pub use permissions::{Permissions, Restrictions};
-pub use reactions::InputReactions;
+pub use poll_vote::PollVote;
+pub use privacy::{PrivacyKey, PrivacyRule, PrivacyRules};
+pub use qr_login_token::QrLoginToken;
+pub use reactions::{
+    InputReactions, MessageReactions, PeerReaction, ReactionChange, ReactionCount, ReactionsUpdate,
+};
 pub(crate) use reply_markup::ReplyMarkup;
+pub use reply_to::{QuoteError, ReplyHeader, ReplyTo};
 pub use terms_of_service::TermsOfService;
 pub use update::Update;
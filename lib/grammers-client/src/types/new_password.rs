@@ -0,0 +1,50 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// A new two-factor authentication password to be set on the account, along with its settings.
+///
+/// # Examples
+///
+/// ```
+/// use grammers_client::types::NewPassword;
+///
+/// let password = NewPassword::new("correct horse battery staple")
+///     .hint("horses")
+///     .email("alice@example.com");
+/// ```
+#[derive(Clone, Debug)]
+pub struct NewPassword {
+    pub(crate) password: Vec<u8>,
+    pub(crate) hint: String,
+    pub(crate) email: Option<String>,
+}
+
+impl NewPassword {
+    /// Create a new password with no hint and no recovery email.
+    pub fn new(password: impl AsRef<[u8]>) -> Self {
+        Self {
+            password: password.as_ref().to_vec(),
+            hint: String::new(),
+            email: None,
+        }
+    }
+
+    /// The hint shown when the password is required, which must differ from the password itself.
+    pub fn hint(mut self, hint: &str) -> Self {
+        self.hint = hint.to_string();
+        self
+    }
+
+    /// The recovery email, which can be used to reset the password if it's forgotten.
+    ///
+    /// The email must be confirmed before the password takes effect.
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+}
@@ -21,4 +21,20 @@ impl PasswordToken {
     pub fn hint(&self) -> Option<&str> {
         self.password.hint.as_deref()
     }
+
+    /// Whether the account has a two-factor authentication password set.
+    pub fn has_password(&self) -> bool {
+        self.password.has_password
+    }
+
+    /// Whether the account has a confirmed recovery email, which can be used to reset the
+    /// password if it's forgotten.
+    pub fn has_recovery(&self) -> bool {
+        self.password.has_recovery
+    }
+
+    /// The pattern of the recovery email that was set up but is pending confirmation, if any.
+    pub fn email_unconfirmed_pattern(&self) -> Option<&str> {
+        self.password.email_unconfirmed_pattern.as_deref()
+    }
 }
@@ -0,0 +1,51 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::types::Chat;
+use crate::utils;
+use chrono::{DateTime, Utc};
+use grammers_tl_types as tl;
+
+/// A vote cast in a poll with public voters, as returned by [`Client::iter_poll_voters`].
+///
+/// [`Client::iter_poll_voters`]: crate::Client::iter_poll_voters
+#[derive(Clone, Debug)]
+pub struct PollVote {
+    pub raw: tl::enums::MessagePeerVote,
+    chat: Chat,
+}
+
+impl PollVote {
+    pub(crate) fn new(raw: tl::enums::MessagePeerVote, chat: Chat) -> Self {
+        Self { raw, chat }
+    }
+
+    /// The user or channel who voted.
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    /// The options of the answers that were chosen, as found in [`Poll::iter_answers`].
+    ///
+    /// This is empty when the voters were fetched for a specific option, since it's that one.
+    ///
+    /// [`Poll::iter_answers`]: crate::types::Poll::iter_answers
+    pub fn options(&self) -> Vec<&[u8]> {
+        match &self.raw {
+            tl::enums::MessagePeerVote::Vote(vote) => vec![&vote.option[..]],
+            tl::enums::MessagePeerVote::InputOption(_) => Vec::new(),
+            tl::enums::MessagePeerVote::Multiple(vote) => {
+                vote.options.iter().map(|option| &option[..]).collect()
+            }
+        }
+    }
+
+    /// When the vote was cast.
+    pub fn date(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date())
+    }
+}
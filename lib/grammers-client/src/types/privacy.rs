@@ -0,0 +1,241 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+
+/// The setting of the account that privacy rules apply to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PrivacyKey {
+    /// Who can see the last seen time and online status.
+    LastSeen,
+    /// Who can invite the account to chats.
+    ChatInvite,
+    /// Who can call the account.
+    PhoneCall,
+    /// Who can connect directly with the account in calls, revealing its IP address.
+    PhoneP2P,
+    /// Who can add a link to the account when forwarding its messages.
+    Forwards,
+    /// Who can see the profile photo.
+    ProfilePhoto,
+    /// Who can see the phone number.
+    PhoneNumber,
+    /// Who can find the account by its phone number.
+    AddedByPhone,
+    /// Who can send voice and video messages.
+    VoiceMessages,
+    /// Who can see the bio.
+    Bio,
+    /// Who can see the birthday.
+    Birthday,
+}
+
+/// A single rule of a privacy setting, as returned by Telegram.
+///
+/// Exceptions for users and chats refer to them by their identifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PrivacyRule {
+    AllowAll,
+    AllowContacts,
+    AllowCloseFriends,
+    AllowPremium,
+    AllowBots,
+    AllowUsers(Vec<i64>),
+    AllowChatParticipants(Vec<i64>),
+    DisallowAll,
+    DisallowContacts,
+    DisallowBots,
+    DisallowUsers(Vec<i64>),
+    DisallowChatParticipants(Vec<i64>),
+}
+
+/// Builder for the rules of a privacy setting, used by [`Client::set_privacy`].
+///
+/// Start with who can access the setting by default, then add exceptions to it. Exceptions take
+/// precedence over the default.
+///
+/// [`Client::set_privacy`]: crate::Client::set_privacy
+///
+/// # Examples
+///
+/// ```
+/// # fn f(best_friend: grammers_client::types::User, group: grammers_client::types::Chat) {
+/// use grammers_client::types::PrivacyRules;
+///
+/// // Contacts, and anyone in the group, except for my best friend.
+/// let rules = PrivacyRules::contacts()
+///     .allow_chat_participants([&group])
+///     .disallow_users([&best_friend]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrivacyRules {
+    exceptions: Vec<tl::enums::InputPrivacyRule>,
+    default: Vec<tl::enums::InputPrivacyRule>,
+}
+
+impl PrivacyKey {
+    pub(crate) fn to_raw(self) -> tl::enums::InputPrivacyKey {
+        use tl::enums::InputPrivacyKey as K;
+        match self {
+            Self::LastSeen => K::StatusTimestamp,
+            Self::ChatInvite => K::ChatInvite,
+            Self::PhoneCall => K::PhoneCall,
+            Self::PhoneP2P => K::PhoneP2P,
+            Self::Forwards => K::Forwards,
+            Self::ProfilePhoto => K::ProfilePhoto,
+            Self::PhoneNumber => K::PhoneNumber,
+            Self::AddedByPhone => K::AddedByPhone,
+            Self::VoiceMessages => K::VoiceMessages,
+            Self::Bio => K::About,
+            Self::Birthday => K::Birthday,
+        }
+    }
+}
+
+impl PrivacyRule {
+    pub(crate) fn from_raw(rule: tl::enums::PrivacyRule) -> Self {
+        use tl::enums::PrivacyRule as R;
+        match rule {
+            R::PrivacyValueAllowAll => Self::AllowAll,
+            R::PrivacyValueAllowContacts => Self::AllowContacts,
+            R::PrivacyValueAllowCloseFriends => Self::AllowCloseFriends,
+            R::PrivacyValueAllowPremium => Self::AllowPremium,
+            R::PrivacyValueAllowBots => Self::AllowBots,
+            R::PrivacyValueAllowUsers(r) => Self::AllowUsers(r.users),
+            R::PrivacyValueAllowChatParticipants(r) => Self::AllowChatParticipants(r.chats),
+            R::PrivacyValueDisallowAll => Self::DisallowAll,
+            R::PrivacyValueDisallowContacts => Self::DisallowContacts,
+            R::PrivacyValueDisallowBots => Self::DisallowBots,
+            R::PrivacyValueDisallowUsers(r) => Self::DisallowUsers(r.users),
+            R::PrivacyValueDisallowChatParticipants(r) => Self::DisallowChatParticipants(r.chats),
+        }
+    }
+}
+
+impl PrivacyRules {
+    /// Everybody can access the setting by default.
+    pub fn everybody() -> Self {
+        Self {
+            exceptions: Vec::new(),
+            default: vec![tl::enums::InputPrivacyRule::InputPrivacyValueAllowAll],
+        }
+    }
+
+    /// Only contacts can access the setting by default.
+    pub fn contacts() -> Self {
+        Self {
+            exceptions: Vec::new(),
+            default: vec![
+                tl::enums::InputPrivacyRule::InputPrivacyValueAllowContacts,
+                tl::enums::InputPrivacyRule::InputPrivacyValueDisallowAll,
+            ],
+        }
+    }
+
+    /// Nobody can access the setting by default.
+    pub fn nobody() -> Self {
+        Self {
+            exceptions: Vec::new(),
+            default: vec![tl::enums::InputPrivacyRule::InputPrivacyValueDisallowAll],
+        }
+    }
+
+    /// Allow the given users to access the setting.
+    pub fn allow_users<C: Into<PackedChat>, I: IntoIterator<Item = C>>(mut self, users: I) -> Self {
+        self.exceptions.push(
+            tl::types::InputPrivacyValueAllowUsers {
+                users: users
+                    .into_iter()
+                    .map(|u| u.into().to_input_user_lossy())
+                    .collect(),
+            }
+            .into(),
+        );
+        self
+    }
+
+    /// Prevent the given users from accessing the setting.
+    pub fn disallow_users<C: Into<PackedChat>, I: IntoIterator<Item = C>>(
+        mut self,
+        users: I,
+    ) -> Self {
+        self.exceptions.push(
+            tl::types::InputPrivacyValueDisallowUsers {
+                users: users
+                    .into_iter()
+                    .map(|u| u.into().to_input_user_lossy())
+                    .collect(),
+            }
+            .into(),
+        );
+        self
+    }
+
+    /// Allow the participants of the given groups to access the setting.
+    pub fn allow_chat_participants<C: Into<PackedChat>, I: IntoIterator<Item = C>>(
+        mut self,
+        chats: I,
+    ) -> Self {
+        self.exceptions.push(
+            tl::types::InputPrivacyValueAllowChatParticipants {
+                chats: chats.into_iter().map(|c| c.into().id).collect(),
+            }
+            .into(),
+        );
+        self
+    }
+
+    /// Prevent the participants of the given groups from accessing the setting.
+    pub fn disallow_chat_participants<C: Into<PackedChat>, I: IntoIterator<Item = C>>(
+        mut self,
+        chats: I,
+    ) -> Self {
+        self.exceptions.push(
+            tl::types::InputPrivacyValueDisallowChatParticipants {
+                chats: chats.into_iter().map(|c| c.into().id).collect(),
+            }
+            .into(),
+        );
+        self
+    }
+
+    /// Allow the close friends of the account to access the setting.
+    pub fn allow_close_friends(mut self) -> Self {
+        self.exceptions
+            .push(tl::enums::InputPrivacyRule::InputPrivacyValueAllowCloseFriends);
+        self
+    }
+
+    /// Allow users with Telegram Premium to access the setting.
+    pub fn allow_premium(mut self) -> Self {
+        self.exceptions
+            .push(tl::enums::InputPrivacyRule::InputPrivacyValueAllowPremium);
+        self
+    }
+
+    /// Allow or prevent bots from accessing the setting.
+    pub fn bots(mut self, allow: bool) -> Self {
+        self.exceptions.push(if allow {
+            tl::enums::InputPrivacyRule::InputPrivacyValueAllowBots
+        } else {
+            tl::enums::InputPrivacyRule::InputPrivacyValueDisallowBots
+        });
+        self
+    }
+
+    pub(crate) fn to_raw(&self) -> Vec<tl::enums::InputPrivacyRule> {
+        self.exceptions
+            .iter()
+            .chain(self.default.iter())
+            .cloned()
+            .collect()
+    }
+}
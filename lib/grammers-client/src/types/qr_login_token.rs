@@ -0,0 +1,82 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::utils;
+use chrono::{DateTime, Utc};
+
+const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A login token which a Telegram application where the account is already logged in can scan
+/// as a QR code in order to authorize this client.
+///
+/// These tokens are produced by [`Client::qr_login`] and are only valid until they
+/// [expire](QrLoginToken::expires).
+///
+/// [`Client::qr_login`]: crate::Client::qr_login
+#[derive(Clone, Debug)]
+pub struct QrLoginToken {
+    pub(crate) token: Vec<u8>,
+    pub(crate) expires: i32,
+}
+
+impl QrLoginToken {
+    /// The raw bytes of the token.
+    pub fn token(&self) -> &[u8] {
+        &self.token
+    }
+
+    /// The URL that should be encoded in the QR code shown to the user, in the form
+    /// `tg://login?token=...`.
+    pub fn url(&self) -> String {
+        format!("tg://login?token={}", encode_base64_url(&self.token))
+    }
+
+    /// When the token expires. A new token will be produced after this date.
+    pub fn expires(&self) -> DateTime<Utc> {
+        utils::date(self.expires)
+    }
+}
+
+/// Encode the data as URL-safe base64 without padding.
+fn encode_base64_url(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            result.push(BASE64_URL_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_base64_url() {
+        assert_eq!(encode_base64_url(b""), "");
+        assert_eq!(encode_base64_url(b"f"), "Zg");
+        assert_eq!(encode_base64_url(b"fo"), "Zm8");
+        assert_eq!(encode_base64_url(b"foo"), "Zm9v");
+        assert_eq!(encode_base64_url(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode_base64_url(&[0xfb, 0xff, 0xfe]), "-__-");
+    }
+
+    #[test]
+    fn check_url() {
+        let token = QrLoginToken {
+            token: b"foob".to_vec(),
+            expires: 0,
+        };
+        assert_eq!(token.url(), "tg://login?token=Zm9vYg");
+    }
+}
@@ -6,6 +6,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use crate::types::{Chat, ChatMap};
+use crate::{Client, utils};
+use chrono::{DateTime, Utc};
 use grammers_tl_types as tl;
 use tl::enums::Reaction;
 
@@ -84,3 +87,277 @@ impl From<InputReactions> for Vec<Reaction> {
         val.reactions
     }
 }
+
+/// How many times a certain reaction was used on a message.
+#[derive(Clone, Debug)]
+pub struct ReactionCount {
+    pub raw: tl::types::ReactionCount,
+}
+
+/// A reaction left on a message by a specific user or channel.
+#[derive(Clone, Debug)]
+pub struct PeerReaction {
+    pub raw: tl::types::MessagePeerReaction,
+    chat: Chat,
+}
+
+/// The reactions of a message, as returned by [`Message::reactions`].
+///
+/// [`Message::reactions`]: crate::types::Message::reactions
+#[derive(Clone, Debug)]
+pub struct MessageReactions {
+    pub raw: tl::types::MessageReactions,
+    counts: Vec<ReactionCount>,
+    recent: Vec<PeerReaction>,
+}
+
+/// The reactions of a message changed, as seen in [`Update::MessageReactionsChanged`].
+///
+/// [`Update::MessageReactionsChanged`]: crate::types::Update::MessageReactionsChanged
+#[derive(Clone, Debug)]
+pub struct ReactionsUpdate {
+    chat: Chat,
+    message_id: i32,
+    topic_id: Option<i32>,
+    reactions: MessageReactions,
+}
+
+/// A user or channel changed their reactions on a message, as seen in
+/// [`Update::MessageReacted`].
+///
+/// Only bot accounts which are administrators in the chat receive this update.
+///
+/// [`Update::MessageReacted`]: crate::types::Update::MessageReacted
+#[derive(Clone, Debug)]
+pub struct ReactionChange {
+    pub raw: tl::types::UpdateBotMessageReaction,
+    chat: Chat,
+    actor: Chat,
+}
+
+impl ReactionCount {
+    pub fn from_raw(count: tl::enums::ReactionCount) -> Self {
+        let tl::enums::ReactionCount::Count(raw) = count;
+        Self { raw }
+    }
+
+    /// The reaction that was used.
+    pub fn reaction(&self) -> &Reaction {
+        &self.raw.reaction
+    }
+
+    /// The emoji of the reaction, if it's not a custom emoji.
+    pub fn emoticon(&self) -> Option<&str> {
+        match &self.raw.reaction {
+            Reaction::Emoji(reaction) => Some(&reaction.emoticon),
+            _ => None,
+        }
+    }
+
+    /// The document identifier of the custom emoji of the reaction, if it's a custom emoji.
+    pub fn custom_emoji_id(&self) -> Option<i64> {
+        match &self.raw.reaction {
+            Reaction::CustomEmoji(reaction) => Some(reaction.document_id),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a paid reaction, made with Telegram Stars.
+    pub fn is_paid(&self) -> bool {
+        matches!(self.raw.reaction, Reaction::Paid)
+    }
+
+    /// How many times the reaction was used.
+    pub fn count(&self) -> i32 {
+        self.raw.count
+    }
+
+    /// Whether the logged-in account chose this reaction.
+    pub fn is_chosen(&self) -> bool {
+        self.raw.chosen_order.is_some()
+    }
+
+    /// If the logged-in account chose this reaction, the order in which it did so among the rest.
+    pub fn chosen_order(&self) -> Option<i32> {
+        self.raw.chosen_order
+    }
+}
+
+impl PeerReaction {
+    pub(crate) fn from_raw(
+        reaction: tl::enums::MessagePeerReaction,
+        chats: &ChatMap,
+        client: &Client,
+    ) -> Self {
+        let tl::enums::MessagePeerReaction::Reaction(raw) = reaction;
+        let chat = utils::always_find_entity(&raw.peer_id, chats, client);
+        Self { raw, chat }
+    }
+
+    /// The user or channel who reacted.
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    /// The reaction that was used.
+    pub fn reaction(&self) -> &Reaction {
+        &self.raw.reaction
+    }
+
+    /// When the reaction was made.
+    pub fn date(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date)
+    }
+
+    /// Whether the reaction was made with a big animation.
+    pub fn is_big(&self) -> bool {
+        self.raw.big
+    }
+
+    /// Whether the reaction is to a message of the logged-in account and has not been seen yet.
+    pub fn is_unread(&self) -> bool {
+        self.raw.unread
+    }
+
+    /// Whether the reaction was made by the logged-in account.
+    pub fn is_my(&self) -> bool {
+        self.raw.my
+    }
+}
+
+impl MessageReactions {
+    pub(crate) fn from_raw(
+        reactions: tl::enums::MessageReactions,
+        chats: &ChatMap,
+        client: &Client,
+    ) -> Self {
+        let tl::enums::MessageReactions::Reactions(raw) = reactions;
+        let counts = raw
+            .results
+            .iter()
+            .cloned()
+            .map(ReactionCount::from_raw)
+            .collect();
+        let recent = raw
+            .recent_reactions
+            .iter()
+            .flatten()
+            .cloned()
+            .map(|reaction| PeerReaction::from_raw(reaction, chats, client))
+            .collect();
+        Self {
+            raw,
+            counts,
+            recent,
+        }
+    }
+
+    /// How many times each reaction was used.
+    pub fn counts(&self) -> &[ReactionCount] {
+        &self.counts
+    }
+
+    /// How many reactions there are in total.
+    pub fn total(&self) -> i32 {
+        self.counts.iter().map(ReactionCount::count).sum()
+    }
+
+    /// The most recent reactions and who made them.
+    ///
+    /// This is only available in small groups and private conversations.
+    pub fn recent_reactions(&self) -> &[PeerReaction] {
+        &self.recent
+    }
+
+    /// Whether the full list of who reacted can be fetched with
+    /// [`Client::iter_message_reactors`].
+    ///
+    /// [`Client::iter_message_reactors`]: crate::Client::iter_message_reactors
+    pub fn can_see_list(&self) -> bool {
+        self.raw.can_see_list
+    }
+
+    /// Whether the counts were sent without the reactions of the logged-in account, in which
+    /// case none will be chosen.
+    pub fn is_min(&self) -> bool {
+        self.raw.min
+    }
+}
+
+impl ReactionsUpdate {
+    pub(crate) fn new(
+        chat: Chat,
+        message_id: i32,
+        topic_id: Option<i32>,
+        reactions: MessageReactions,
+    ) -> Self {
+        Self {
+            chat,
+            message_id,
+            topic_id,
+            reactions,
+        }
+    }
+
+    /// The chat where the message is.
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    /// The identifier of the message whose reactions changed.
+    pub fn message_id(&self) -> i32 {
+        self.message_id
+    }
+
+    /// The forum topic where the message is, if known and it's not "General".
+    pub fn topic_id(&self) -> Option<i32> {
+        self.topic_id
+    }
+
+    /// The new reactions of the message.
+    pub fn reactions(&self) -> &MessageReactions {
+        &self.reactions
+    }
+}
+
+impl ReactionChange {
+    pub(crate) fn from_raw(
+        raw: tl::types::UpdateBotMessageReaction,
+        chats: &ChatMap,
+        client: &Client,
+    ) -> Self {
+        let chat = utils::always_find_entity(&raw.peer, chats, client);
+        let actor = utils::always_find_entity(&raw.actor, chats, client);
+        Self { raw, chat, actor }
+    }
+
+    /// The chat where the message is.
+    pub fn chat(&self) -> &Chat {
+        &self.chat
+    }
+
+    /// The identifier of the message whose reactions changed.
+    pub fn message_id(&self) -> i32 {
+        self.raw.msg_id
+    }
+
+    /// The user or channel who changed their reactions.
+    pub fn actor(&self) -> &Chat {
+        &self.actor
+    }
+
+    /// When the reactions were changed.
+    pub fn date(&self) -> DateTime<Utc> {
+        utils::date(self.raw.date)
+    }
+
+    /// The reactions before the change.
+    pub fn old_reactions(&self) -> &[Reaction] {
+        &self.raw.old_reactions
+    }
+
+    /// The reactions after the change.
+    pub fn new_reactions(&self) -> &[Reaction] {
+        &self.raw.new_reactions
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use crate::types::Chat;
+use crate::utils::{entity_range, telegram_string_len};
+use grammers_session::PackedChat;
+use grammers_tl_types as tl;
+use std::fmt;
+
+/// Builder for what a message should reply to, used by [`InputMessage::reply_header`].
+///
+/// Unlike [`InputMessage::reply_to`], this can reply to messages in other chats, quote only
+/// part of the replied message, or reply to stories.
+///
+/// [`InputMessage::reply_header`]: crate::InputMessage::reply_header
+/// [`InputMessage::reply_to`]: crate::InputMessage::reply_to
+///
+/// # Examples
+///
+/// ```
+/// # async fn f(chat: grammers_client::types::Chat, other: grammers_client::types::Chat, client: grammers_client::Client) -> Result<(), Box<dyn std::error::Error>> {
+/// use grammers_client::InputMessage;
+/// use grammers_client::types::ReplyTo;
+///
+/// // Reply to the message with ID 123 in another chat, quoting its first word.
+/// let reply_to = ReplyTo::message(123).chat(&other).quote("Hello", Vec::new(), 0)?;
+/// client
+///     .send_message(&chat, InputMessage::text("Hi!").reply_header(Some(reply_to)))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReplyTo {
+    pub raw: tl::enums::InputReplyTo,
+}
+
+/// The error returned by [`ReplyTo::quote`] when the quote is not valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteError {
+    /// The offset of the quote within the replied message was negative.
+    NegativeOffset,
+    /// One of the formatting entities fell outside the quoted text.
+    EntityOutOfBounds,
+}
+
+impl fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegativeOffset => write!(f, "quote error: offset must not be negative"),
+            Self::EntityOutOfBounds => {
+                write!(f, "quote error: entities must be within the quoted text")
+            }
+        }
+    }
+}
+
+impl std::error::Error for QuoteError {}
+
+/// Information about what a [`Message`] is replying to.
+///
+/// [`Message`]: crate::types::Message
+#[derive(Clone, Debug)]
+pub struct ReplyHeader {
+    pub raw: tl::enums::MessageReplyHeader,
+    chat: Option<Chat>,
+}
+
+impl ReplyTo {
+    /// Reply to the message with the given identifier, in the same chat the reply is sent to.
+    pub fn message(message_id: i32) -> Self {
+        Self {
+            raw: tl::types::InputReplyToMessage {
+                reply_to_msg_id: message_id,
+                top_msg_id: None,
+                reply_to_peer_id: None,
+                quote_text: None,
+                quote_entities: None,
+                quote_offset: None,
+            }
+            .into(),
+        }
+    }
+
+    /// Reply to the story with the given identifier posted by a user or channel.
+    pub fn story<C: Into<PackedChat>>(chat: C, story_id: i32) -> Self {
+        Self {
+            raw: tl::types::InputReplyToStory {
+                peer: chat.into().to_input_peer(),
+                story_id,
+            }
+            .into(),
+        }
+    }
+
+    /// The chat where the replied message is, if it's not the chat where the reply is sent to.
+    ///
+    /// This has no effect when replying to stories.
+    pub fn chat<C: Into<PackedChat>>(mut self, chat: C) -> Self {
+        if let tl::enums::InputReplyTo::Message(reply_to) = &mut self.raw {
+            reply_to.reply_to_peer_id = Some(chat.into().to_input_peer());
+        }
+        self
+    }
+
+    /// Quote only part of the replied message.
+    ///
+    /// The quoted `text` starts at `offset` within the text of the replied message, and may
+    /// contain formatting `entities`. As with the rest of Telegram, offsets and lengths are
+    /// measured in UTF-16 code units.
+    ///
+    /// This has no effect when replying to stories.
+    ///
+    /// Fails if `offset` is negative or if any of the entities falls outside the quoted text.
+    pub fn quote<S: Into<String>>(
+        mut self,
+        text: S,
+        entities: Vec<tl::enums::MessageEntity>,
+        offset: i32,
+    ) -> Result<Self, QuoteError> {
+        let text = text.into();
+        if offset < 0 {
+            return Err(QuoteError::NegativeOffset);
+        }
+        let len = telegram_string_len(&text);
+        if !entities
+            .iter()
+            .map(entity_range)
+            .all(|(entity_offset, entity_len)| {
+                entity_offset >= 0
+                    && entity_len >= 0
+                    && entity_offset
+                        .checked_add(entity_len)
+                        .is_some_and(|end| end <= len)
+            })
+        {
+            return Err(QuoteError::EntityOutOfBounds);
+        }
+
+        if let tl::enums::InputReplyTo::Message(reply_to) = &mut self.raw {
+            reply_to.quote_text = Some(text);
+            reply_to.quote_entities = if entities.is_empty() {
+                None
+            } else {
+                Some(entities)
+            };
+            reply_to.quote_offset = Some(offset);
+        }
+        Ok(self)
+    }
+}
+
+/// Combine what a message replies to with the topic it is posted into.
+pub(crate) fn input_reply_to(
+    reply_to: Option<&ReplyTo>,
+    topic_id: Option<i32>,
+) -> Option<tl::enums::InputReplyTo> {
+    match reply_to {
+        Some(ReplyTo {
+            raw: tl::enums::InputReplyTo::Message(reply_to),
+        }) => {
+            let mut reply_to = reply_to.clone();
+            reply_to.top_msg_id = reply_to.top_msg_id.or(topic_id);
+            Some(reply_to.into())
+        }
+        Some(reply_to) => Some(reply_to.raw.clone()),
+        // Posting into a topic is done by replying to the message that created it.
+        None => topic_id.map(|reply_to_msg_id| ReplyTo::message(reply_to_msg_id).raw),
+    }
+}
+
+impl ReplyHeader {
+    pub(crate) fn from_raw(raw: tl::enums::MessageReplyHeader, chat: Option<Chat>) -> Self {
+        Self { raw, chat }
+    }
+
+    /// The identifier of the replied message, if it is a reply to a message that is known.
+    pub fn message_id(&self) -> Option<i32> {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(header) => header.reply_to_msg_id,
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(_) => None,
+        }
+    }
+
+    /// The identifier of the replied story, if it is a reply to a story.
+    pub fn story_id(&self) -> Option<i32> {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(_) => None,
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(header) => Some(header.story_id),
+        }
+    }
+
+    /// The chat of the replied message, if it's not the chat where the reply is, or the chat
+    /// that posted the replied story.
+    pub fn chat(&self) -> Option<&Chat> {
+        self.chat.as_ref()
+    }
+
+    /// The forum topic where the reply was posted, if it's not "General".
+    pub fn topic_id(&self) -> Option<i32> {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(header) if header.forum_topic => {
+                // Messages directly in the topic "reply" to the message that created it.
+                header.reply_to_top_id.or(header.reply_to_msg_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the replied message is a scheduled message.
+    pub fn is_scheduled(&self) -> bool {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(header) => header.reply_to_scheduled,
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(_) => false,
+        }
+    }
+
+    /// The part of the replied message that was quoted, if any.
+    pub fn quote_text(&self) -> Option<&str> {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(header) => header.quote_text.as_deref(),
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(_) => None,
+        }
+    }
+
+    /// The formatting entities within the quoted text, if any.
+    pub fn quote_entities(&self) -> Option<&[tl::enums::MessageEntity]> {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(header) => header.quote_entities.as_deref(),
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(_) => None,
+        }
+    }
+
+    /// Where the quoted text starts within the text of the replied message, if known.
+    pub fn quote_offset(&self) -> Option<i32> {
+        match &self.raw {
+            tl::enums::MessageReplyHeader::Header(header) => header.quote_offset,
+            tl::enums::MessageReplyHeader::MessageReplyStoryHeader(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_only_replies_to_topic() {
+        let raw = input_reply_to(None, Some(7)).unwrap();
+        let tl::enums::InputReplyTo::Message(reply_to) = raw else {
+            panic!("expected a reply to a message");
+        };
+        assert_eq!(reply_to.reply_to_msg_id, 7);
+        assert_eq!(reply_to.top_msg_id, None);
+    }
+
+    #[test]
+    fn reply_inside_topic() {
+        let reply_to = ReplyTo::message(10);
+        let raw = input_reply_to(Some(&reply_to), Some(7)).unwrap();
+        let tl::enums::InputReplyTo::Message(reply_to) = raw else {
+            panic!("expected a reply to a message");
+        };
+        assert_eq!(reply_to.reply_to_msg_id, 10);
+        assert_eq!(reply_to.top_msg_id, Some(7));
+    }
+
+    #[test]
+    fn quote_entity_out_of_bounds() {
+        let result = ReplyTo::message(10).quote(
+            "🦀 ok",
+            vec![
+                tl::types::MessageEntityBold {
+                    offset: 3,
+                    length: 3,
+                }
+                .into(),
+            ],
+            0,
+        );
+        assert_eq!(result.err(), Some(QuoteError::EntityOutOfBounds));
+    }
+
+    #[test]
+    fn quote_entity_overflowing_bounds() {
+        let result = ReplyTo::message(10).quote(
+            "ok",
+            vec![
+                tl::types::MessageEntityBold {
+                    offset: i32::MAX,
+                    length: 1,
+                }
+                .into(),
+            ],
+            0,
+        );
+        assert_eq!(result.err(), Some(QuoteError::EntityOutOfBounds));
+    }
+
+    #[test]
+    fn quote_negative_offset() {
+        let result = ReplyTo::message(10).quote("ok", Vec::new(), -1);
+        assert_eq!(result.err(), Some(QuoteError::NegativeOffset));
+    }
+
+    #[test]
+    fn quote_entity_within_bounds() {
+        let reply_to = ReplyTo::message(10)
+            .quote(
+                "🦀 ok",
+                vec![
+                    tl::types::MessageEntityBold {
+                        offset: 3,
+                        length: 2,
+                    }
+                    .into(),
+                ],
+                4,
+            )
+            .unwrap();
+        let tl::enums::InputReplyTo::Message(reply_to) = reply_to.raw else {
+            panic!("expected a reply to a message");
+        };
+        assert_eq!(reply_to.quote_text.as_deref(), Some("🦀 ok"));
+        assert_eq!(reply_to.quote_offset, Some(4));
+        assert_eq!(reply_to.quote_entities.map(|e| e.len()), Some(1));
+    }
+}
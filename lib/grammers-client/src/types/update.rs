@@ -8,8 +8,15 @@
 
 use std::sync::Arc;
 
-use super::{CallbackQuery, ChatMap, InlineQuery, InlineSend, Message};
-use crate::{Client, types::MessageDeletion};
+use super::{
+    CallbackQuery, ChatMap, Draft, InlineQuery, InlineSend, Message, MessageReactions,
+    ReactionChange, ReactionsUpdate,
+};
+use crate::{
+    Client,
+    types::{MessageDeletion, ScheduledMessageDeletion},
+    utils,
+};
 use grammers_tl_types as tl;
 
 #[non_exhaustive]
@@ -21,6 +28,20 @@ pub enum Update {
     MessageEdited(Message),
     /// Occurs when a message is deleted.
     MessageDeleted(MessageDeletion),
+    /// Occurs when a message is scheduled to be sent later.
+    ScheduledMessageCreated(Message),
+    /// Occurs when scheduled messages are deleted, or sent.
+    ScheduledMessageDeleted(ScheduledMessageDeletion),
+    /// Occurs when the draft of a chat is changed or cleared, possibly from a different session.
+    DraftChanged(Draft),
+    /// Occurs when the reactions of a message change.
+    ///
+    /// Bot accounts only receive this for channels and groups where they are administrators,
+    /// without knowing who reacted.
+    MessageReactionsChanged(ReactionsUpdate),
+    /// Occurs when someone changes their reactions on a message in a chat where the logged-in
+    /// bot account is an administrator.
+    MessageReacted(ReactionChange),
     /// Occurs when Telegram calls back into your bot because an inline callback
     /// button was pressed.
     CallbackQuery(CallbackQuery),
@@ -73,6 +94,70 @@ impl Update {
                 messages, channel_id,
             ))),
 
+            // ScheduledMessageCreated
+            tl::enums::Update::NewScheduledMessage(tl::types::UpdateNewScheduledMessage {
+                message,
+            }) => Message::from_raw(client, message, chats).map(Self::ScheduledMessageCreated),
+
+            // ScheduledMessageDeleted
+            tl::enums::Update::DeleteScheduledMessages(raw) => {
+                Some(Self::ScheduledMessageDeleted(ScheduledMessageDeletion {
+                    raw,
+                }))
+            }
+
+            // DraftChanged
+            tl::enums::Update::DraftMessage(tl::types::UpdateDraftMessage {
+                peer,
+                top_msg_id,
+                draft,
+            }) => Some(Self::DraftChanged(Draft::new(
+                draft,
+                utils::always_find_entity(&peer, chats, client),
+                top_msg_id,
+            ))),
+
+            // MessageReactionsChanged
+            tl::enums::Update::MessageReactions(tl::types::UpdateMessageReactions {
+                peer,
+                msg_id,
+                top_msg_id,
+                reactions,
+            }) => Some(Self::MessageReactionsChanged(ReactionsUpdate::new(
+                utils::always_find_entity(&peer, chats, client),
+                msg_id,
+                top_msg_id,
+                MessageReactions::from_raw(reactions, chats, client),
+            ))),
+            tl::enums::Update::BotMessageReactions(tl::types::UpdateBotMessageReactions {
+                peer,
+                msg_id,
+                reactions,
+                ..
+            }) => Some(Self::MessageReactionsChanged(ReactionsUpdate::new(
+                utils::always_find_entity(&peer, chats, client),
+                msg_id,
+                None,
+                MessageReactions::from_raw(
+                    tl::types::MessageReactions {
+                        min: true,
+                        can_see_list: false,
+                        reactions_as_tags: false,
+                        results: reactions,
+                        recent_reactions: None,
+                        top_reactors: None,
+                    }
+                    .into(),
+                    chats,
+                    client,
+                ),
+            ))),
+
+            // MessageReacted
+            tl::enums::Update::BotMessageReaction(update) => Some(Self::MessageReacted(
+                ReactionChange::from_raw(update, chats, client),
+            )),
+
             // CallbackQuery
             tl::enums::Update::BotCallbackQuery(query) => Some(Self::CallbackQuery(
                 CallbackQuery::from_raw(client, query, chats),
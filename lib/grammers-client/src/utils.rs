@@ -8,6 +8,7 @@
 
 use crate::types;
 use chrono::{DateTime, Utc};
+use grammers_mtsender::{InvocationError, RpcError};
 use grammers_session::{PackedChat, PackedType};
 use grammers_tl_types as tl;
 use std::sync::atomic::{AtomicI64, Ordering};
@@ -19,6 +20,18 @@ use web_time::SystemTime;
 // are not actually unique which could confuse some of the API results.
 static LAST_ID: AtomicI64 = AtomicI64::new(0);
 
+/// Build the error Telegram would respond with for a request with invalid arguments, for
+/// requests that are known to fail before being sent, such as when a chat of the wrong kind is
+/// used.
+pub(crate) fn bad_request(name: &str) -> InvocationError {
+    InvocationError::Rpc(RpcError {
+        code: 400,
+        name: name.to_string(),
+        value: None,
+        caused_by: None,
+    })
+}
+
 /// Generate a "random" ID suitable for sending messages or media.
 pub(crate) fn generate_random_id() -> i64 {
     while LAST_ID.load(Ordering::SeqCst) == 0 {
@@ -42,6 +55,43 @@ pub(crate) fn generate_random_ids(n: usize) -> Vec<i64> {
     (0..n).map(|_| generate_random_id()).collect()
 }
 
+/// The length of a string, according to Telegram.
+///
+/// Telegram considers the length of the string with surrogate pairs.
+pub(crate) fn telegram_string_len(string: &str) -> i32 {
+    // https://en.wikipedia.org/wiki/Plane_(Unicode)#Overview
+    string.encode_utf16().count() as i32
+}
+
+/// The offset and length of a formatting entity.
+pub(crate) fn entity_range(entity: &tl::enums::MessageEntity) -> (i32, i32) {
+    use tl::enums::MessageEntity as ME;
+
+    match entity {
+        ME::Unknown(e) => (e.offset, e.length),
+        ME::Mention(e) => (e.offset, e.length),
+        ME::Hashtag(e) => (e.offset, e.length),
+        ME::BotCommand(e) => (e.offset, e.length),
+        ME::Url(e) => (e.offset, e.length),
+        ME::Email(e) => (e.offset, e.length),
+        ME::Bold(e) => (e.offset, e.length),
+        ME::Italic(e) => (e.offset, e.length),
+        ME::Code(e) => (e.offset, e.length),
+        ME::Pre(e) => (e.offset, e.length),
+        ME::TextUrl(e) => (e.offset, e.length),
+        ME::MentionName(e) => (e.offset, e.length),
+        ME::InputMessageEntityMentionName(e) => (e.offset, e.length),
+        ME::Phone(e) => (e.offset, e.length),
+        ME::Cashtag(e) => (e.offset, e.length),
+        ME::Underline(e) => (e.offset, e.length),
+        ME::Strike(e) => (e.offset, e.length),
+        ME::BankCard(e) => (e.offset, e.length),
+        ME::Spoiler(e) => (e.offset, e.length),
+        ME::CustomEmoji(e) => (e.offset, e.length),
+        ME::Blockquote(e) => (e.offset, e.length),
+    }
+}
+
 pub(crate) fn date(date: i32) -> DateTime<Utc> {
     DateTime::<Utc>::from_timestamp(date as i64, 0).expect("date out of range")
 }
@@ -5,6 +5,7 @@
 // <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
+use getrandom::getrandom;
 use glass_pumpkin::safe_prime;
 use hmac::Hmac;
 use num_bigint::{BigInt, BigUint, Sign};
@@ -86,6 +87,39 @@ pub fn calculate_2fa(
     (m1, g_a)
 }
 
+/// Calculate the hash that Telegram stores in order to verify the new password, defined as
+/// `v := pow(g, x) mod p`, where `x := PH2(password, salt1, salt2)`.
+///
+/// The `salt1` should come from [`generate_salt1`], so that every password uses a different salt.
+///
+/// The algorithm is described in <https://core.telegram.org/api/srp#setting-a-new-2fa-password>.
+pub fn calculate_password_hash(
+    salt1: &[u8],
+    salt2: &[u8],
+    p: &[u8],
+    g: &i32,
+    password: impl AsRef<[u8]>,
+) -> [u8; 256] {
+    let big_p = BigInt::from_bytes_be(Sign::Plus, p);
+    let big_g = BigInt::from(*g as u32);
+
+    // x := PH2(password, salt1, salt2)
+    let x = ph2(&password, salt1, salt2);
+    let x = BigInt::from_bytes_be(Sign::Plus, &x);
+
+    // v := pow(g, x) mod p
+    let big_v = big_g.modpow(&x, &big_p);
+    pad_to_256(&big_v.to_bytes_be().1)
+}
+
+/// Generate the `salt1` to use for a new password, by appending 32 random bytes to the server's.
+pub fn generate_salt1(server_salt1: &[u8]) -> Vec<u8> {
+    let mut salt1 = vec![0; server_salt1.len() + 32];
+    salt1[..server_salt1.len()].copy_from_slice(server_salt1);
+    getrandom(&mut salt1[server_salt1.len()..]).expect("failed to generate a secure salt");
+    salt1
+}
+
 /// Validation for parameters required for two-factor authentication
 pub fn check_p_and_g(p: &[u8], g: &i32) -> bool {
     if !check_p_len(p) {
@@ -302,6 +336,27 @@ mod tests {
         assert_eq!(expected_g_a, g_a);
     }
 
+    #[test]
+    fn check_password_hash() {
+        let salt1 = vec![1];
+        let salt2 = vec![2];
+        let g = 3;
+        let p = pad_to_256(&[47]);
+        let password = vec![7];
+
+        let v = calculate_password_hash(&salt1, &salt2, &p, &g, password);
+
+        assert_eq!(v, pad_to_256(&[8]));
+    }
+
+    #[test]
+    fn check_generated_salt() {
+        let salt1 = generate_salt1(&[1, 2, 3]);
+        assert_eq!(salt1.len(), 35);
+        assert_eq!(&salt1[..3], &[1, 2, 3]);
+        assert_ne!(generate_salt1(&[1, 2, 3]), salt1);
+    }
+
     #[test]
     fn test_check_p_and_g() {
         // Not prime
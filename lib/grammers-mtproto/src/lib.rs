@@ -33,3 +33,10 @@ pub const DEFAULT_COMPRESSION_THRESHOLD: Option<usize> = Some(512);
 /// you will know the response corresponds to it.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
 pub struct MsgId(i64);
+
+impl MsgId {
+    /// The raw value of the message identifier, as known by the server.
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+}
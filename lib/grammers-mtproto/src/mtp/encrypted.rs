@@ -223,6 +223,18 @@ impl Encrypted {
         MsgId(msg_id)
     }
 
+    /// Serializes the acknowledgement for all pending messages, if any.
+    fn push_pending_ack(&mut self, buffer: &mut DequeBuffer<u8>) {
+        if !self.pending_ack.is_empty() {
+            // TODO avoid to_bytes here, serialize it in-place
+            let body = tl::enums::MsgsAck::Ack(tl::types::MsgsAck {
+                msg_ids: mem::take(&mut self.pending_ack),
+            })
+            .to_bytes();
+            self.serialize_msg(buffer, &body, false);
+        }
+    }
+
     fn get_current_salt(&self) -> i64 {
         self.salts.last().map(|s| s.salt).unwrap_or(0)
     }
@@ -462,17 +474,23 @@ impl Encrypted {
             },
 
             // Cancellation of an RPC Query
-            tl::types::RpcAnswerUnknown::CONSTRUCTOR_ID => {
-                // The `msg_id` corresponds to the `rpc_drop_answer` request.
-            }
-            tl::types::RpcAnswerDroppedRunning::CONSTRUCTOR_ID => {
-                // We will receive two `rpc_result`, one with the `msg_id` of
-                // `rpc_drop_answer` request and other for the original RPC.
-            }
-            tl::types::RpcAnswerDropped::CONSTRUCTOR_ID => {
-                // "the RPC response was removed from the server's outgoing
-                // queue, and its msg_id, seq_no, and length in bytes are
-                // transmitted to the client."
+            //
+            // The `msg_id` corresponds to the `rpc_drop_answer` request, except for
+            // `rpc_answer_dropped_running`, where we will receive two `rpc_result`, one with
+            // the `msg_id` of `rpc_drop_answer` request and other for the original RPC.
+            //
+            // For `rpc_answer_dropped`, "the RPC response was removed from the server's outgoing
+            // queue, and its msg_id, seq_no, and length in bytes are transmitted to the client."
+            //
+            // Either way, the result is propagated so that the request can be considered done.
+            tl::types::RpcAnswerUnknown::CONSTRUCTOR_ID
+            | tl::types::RpcAnswerDroppedRunning::CONSTRUCTOR_ID
+            | tl::types::RpcAnswerDropped::CONSTRUCTOR_ID => {
+                self.deserialization
+                    .push(Deserialization::RpcResult(RpcResult {
+                        msg_id,
+                        body: result,
+                    }));
             }
 
             // Response to an RPC query
@@ -1215,14 +1233,7 @@ impl Mtp for Encrypted {
         // If we need to acknowledge messages, this notification goes in with the rest of requests
         // so that we can also include it. It has priority over user requests because these should
         // be sent out as soon as possible.
-        if !self.pending_ack.is_empty() {
-            // TODO avoid to_bytes here, serialize it in-place
-            let body = tl::enums::MsgsAck::Ack(tl::types::MsgsAck {
-                msg_ids: mem::take(&mut self.pending_ack),
-            })
-            .to_bytes();
-            self.serialize_msg(buffer, &body, false);
-        }
+        self.push_pending_ack(buffer);
 
         // Serialize `MAXIMUM_LENGTH` requests at most.
         if self.msg_count == manual_tl::MessageContainer::MAXIMUM_LENGTH {
@@ -1272,6 +1283,25 @@ impl Mtp for Encrypted {
         }
     }
 
+    /// Serializes a `http_wait` service message, which does not require an acknowledgement.
+    ///
+    /// The server will respond as soon as at least one message is available for this session,
+    /// or with an empty container after `max_wait` milliseconds.
+    fn push_http_wait(&mut self, buffer: &mut DequeBuffer<u8>, max_wait: i32) -> Option<MsgId> {
+        if self.msg_count == manual_tl::MessageContainer::MAXIMUM_LENGTH {
+            return None;
+        }
+
+        self.push_pending_ack(buffer);
+        let body = tl::enums::HttpWait::Wait(tl::types::HttpWait {
+            max_delay: 0,
+            wait_after: 0,
+            max_wait,
+        })
+        .to_bytes();
+        Some(self.serialize_msg(buffer, &body, false))
+    }
+
     /// Processes an encrypted response from the server.
     fn deserialize(&mut self, payload: &[u8]) -> Result<Vec<Deserialization>, DeserializeError> {
         crate::utils::check_message_buffer(payload)?;
@@ -1369,6 +1399,24 @@ mod tests {
         ensure_buffer_is_message(buffer, REQUEST, 1);
     }
 
+    #[test]
+    fn ensure_http_wait_is_not_content_related() {
+        let mut buffer = DequeBuffer::with_capacity(0, 0);
+        let mut mtproto = Encrypted::build().finish(auth_key());
+
+        assert!(mtproto.push_http_wait(&mut buffer, 25_000).is_some());
+        mtproto.finalize_plain(&mut buffer);
+
+        let body = tl::enums::HttpWait::Wait(tl::types::HttpWait {
+            max_delay: 0,
+            wait_after: 0,
+            max_wait: 25_000,
+        })
+        .to_bytes();
+        let buffer = &buffer[MESSAGE_PREFIX_LEN..];
+        ensure_buffer_is_message(buffer, &body, 0);
+    }
+
     #[test]
     fn ensure_correct_multi_serialization() {
         let mut buffer = DequeBuffer::with_capacity(0, 0);
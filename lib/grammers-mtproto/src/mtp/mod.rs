@@ -203,6 +203,20 @@ pub trait Mtp {
     /// This will either belong to the container (if used) or the last serialized message.
     fn finalize(&mut self, buffer: &mut DequeBuffer<u8>) -> Option<MsgId>;
 
+    /// Serializes a long poll request to the input buffer, asking the server to wait up to
+    /// `max_wait` milliseconds for messages to become available before responding.
+    ///
+    /// This only makes sense for transports which [require polling]. The same buffer should be
+    /// used until `finalize` is called.
+    ///
+    /// Returns the message ID assigned to the long poll if it was serialized, or `None` if the
+    /// protocol does not support long polling or the buffer is full.
+    ///
+    /// [require polling]: crate::transport::Transport::requires_polling
+    fn push_http_wait(&mut self, _buffer: &mut DequeBuffer<u8>, _max_wait: i32) -> Option<MsgId> {
+        None
+    }
+
     /// Deserializes a single incoming message payload into zero or more responses.
     fn deserialize(&mut self, payload: &[u8]) -> Result<Vec<Deserialization>, DeserializeError>;
 
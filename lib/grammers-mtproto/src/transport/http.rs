@@ -0,0 +1,309 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use super::{Error, Transport, UnpackedOffset};
+use grammers_crypto::DequeBuffer;
+
+/// The end of the HTTP headers, separating them from the body.
+const HEADER_END: &[u8] = b"\r\n\r\n";
+
+/// The largest body accepted, which matches the size of the buffer the sender reads into.
+const MAXIMUM_DATA: usize = (1024 * 1024) + (8 * 1024);
+
+/// A transport for networks where only plain HTTP requests can be made.
+/// This is an implementation of the [HTTP transport].
+///
+/// * Overhead: large.
+/// * Minimum envelope length: however long the HTTP headers are.
+/// * Maximum envelope length: however long the HTTP headers are.
+///
+/// Every packet is sent as the body of a `POST` request to `/api`, and every
+/// response is the body of the corresponding HTTP response:
+///
+/// ```text
+/// POST /api HTTP/1.1\r\n
+/// Host: 149.154.167.51:80\r\n
+/// Content-Length: 123\r\n
+/// \r\n
+/// payload
+/// ```
+///
+/// Because the server can only send data as a response to a request, a request
+/// must be kept open for the server to be able to send updates. This transport
+/// [requires polling], and the sender will issue `http_wait` requests as needed.
+///
+/// [HTTP transport]: https://core.telegram.org/mtproto#http-transport
+/// [requires polling]: Transport::requires_polling
+pub struct Http {
+    host: String,
+}
+
+impl Http {
+    /// Creates a new HTTP transport, which will set the `Host` header to the given value.
+    ///
+    /// The host should include the port if it's not the default HTTP port, e.g.
+    /// `149.154.167.51:443`.
+    pub fn new(host: impl Into<String>) -> Self {
+        Self { host: host.into() }
+    }
+}
+
+impl Transport for Http {
+    fn pack(&mut self, buffer: &mut DequeBuffer<u8>) {
+        let len = buffer.len();
+        assert_eq!(len % 4, 0);
+
+        let header = format!(
+            "POST /api HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Content-Type: application/octet-stream\r\n\
+             Connection: keep-alive\r\n\
+             Keep-Alive: timeout=100000, max=10000000\r\n\
+             Content-Length: {}\r\n\r\n",
+            self.host, len
+        );
+        buffer.extend_front(header.as_bytes());
+    }
+
+    fn unpack(&mut self, buffer: &mut [u8]) -> Result<UnpackedOffset, Error> {
+        let header_len = match buffer
+            .windows(HEADER_END.len())
+            .position(|w| w == HEADER_END)
+        {
+            Some(pos) => pos + HEADER_END.len(),
+            None => return Err(Error::MissingBytes),
+        };
+
+        let header = std::str::from_utf8(&buffer[..header_len]).map_err(|_| Error::BadHeader)?;
+        let mut lines = header.split("\r\n");
+
+        // HTTP/1.1 200 OK
+        let status = lines
+            .next()
+            .and_then(|line| line.split(' ').nth(1))
+            .and_then(|status| status.parse::<u32>().ok())
+            .ok_or(Error::BadHeader)?;
+
+        if status != 200 {
+            return Err(Error::BadStatus { status });
+        }
+
+        let len = lines
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .ok_or(Error::BadHeader)?;
+
+        // The length comes from the network, so it must be bounded before it's trusted.
+        let end = match header_len.checked_add(len) {
+            Some(end) if len <= MAXIMUM_DATA => end,
+            _ => {
+                return Err(Error::BadLen {
+                    got: i32::try_from(len).unwrap_or(i32::MAX),
+                });
+            }
+        };
+
+        if buffer.len() < end {
+            return Err(Error::MissingBytes);
+        }
+
+        if len <= 4 {
+            if len == 4 {
+                let data =
+                    i32::from_le_bytes(buffer[header_len..header_len + 4].try_into().unwrap());
+                if data < 0 {
+                    return Err(Error::BadStatus {
+                        status: data.unsigned_abs(),
+                    });
+                }
+            }
+            return Err(Error::BadLen { got: len as i32 });
+        }
+
+        Ok(UnpackedOffset {
+            data_start: header_len,
+            data_end: end,
+            next_offset: end,
+        })
+    }
+
+    fn reset(&mut self) {}
+
+    fn requires_polling(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "127.0.0.1:80";
+
+    /// Returns a new HTTP transport, and `n` bytes of input data for it.
+    fn setup_pack(n: usize) -> (Http, DequeBuffer<u8>) {
+        let mut buffer = DequeBuffer::with_capacity(n, 0);
+        buffer.extend((0..n).map(|x| (x & 0xff) as u8));
+        (Http::new(HOST), buffer)
+    }
+
+    /// Wraps the payload in a HTTP response with the given status.
+    fn response(status: &str, payload: &[u8]) -> Vec<u8> {
+        let mut buffer = format!(
+            "HTTP/1.1 {}\r\nConnection: keep-alive\r\ncontent-length: {}\r\n\r\n",
+            status,
+            payload.len()
+        )
+        .into_bytes();
+        buffer.extend(payload);
+        buffer
+    }
+
+    #[test]
+    fn pack_empty() {
+        let (mut transport, mut buffer) = setup_pack(0);
+        transport.pack(&mut buffer);
+
+        let packed = std::str::from_utf8(&buffer[..]).unwrap();
+        assert!(packed.starts_with("POST /api HTTP/1.1\r\n"));
+        assert!(packed.contains("Host: 127.0.0.1:80\r\n"));
+        assert!(packed.ends_with("Content-Length: 0\r\n\r\n"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pack_non_padded() {
+        let (mut transport, mut buffer) = setup_pack(7);
+        transport.pack(&mut buffer);
+    }
+
+    #[test]
+    fn pack_normal() {
+        let (mut transport, mut buffer) = setup_pack(128);
+        let orig = buffer.clone();
+        transport.pack(&mut buffer);
+
+        let header_len = buffer.len() - orig.len();
+        let header = std::str::from_utf8(&buffer[..header_len]).unwrap();
+        assert!(header.ends_with("Content-Length: 128\r\n\r\n"));
+        assert_eq!(&buffer[header_len..], &orig[..]);
+    }
+
+    #[test]
+    fn unpack_small() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = b"HTTP/1.1 200 OK\r\n".to_vec();
+        assert_eq!(transport.unpack(&mut buffer[..]), Err(Error::MissingBytes));
+    }
+
+    #[test]
+    fn unpack_incomplete_body() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = response("200 OK", &[1; 128]);
+        buffer.truncate(buffer.len() - 1);
+        assert_eq!(transport.unpack(&mut buffer[..]), Err(Error::MissingBytes));
+    }
+
+    #[test]
+    fn unpack_oversized_length() {
+        let mut transport = Http::new(HOST);
+        let mut buffer =
+            b"HTTP/1.1 200 OK\r\nContent-Length: 1073741824\r\n\r\n\x01\x02\x03\x04".to_vec();
+        assert_eq!(
+            transport.unpack(&mut buffer[..]),
+            Err(Error::BadLen { got: 1073741824 })
+        );
+    }
+
+    #[test]
+    fn unpack_overflowing_length() {
+        let mut transport = Http::new(HOST);
+        let mut buffer =
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", usize::MAX).into_bytes();
+        assert_eq!(
+            transport.unpack(&mut buffer[..]),
+            Err(Error::BadLen { got: i32::MAX })
+        );
+    }
+
+    #[test]
+    fn unpack_normal() {
+        let mut transport = Http::new(HOST);
+        let orig = (0..128).collect::<Vec<u8>>();
+        let mut buffer = response("200 OK", &orig);
+        let offset = transport.unpack(&mut buffer[..]).unwrap();
+        assert_eq!(&buffer[offset.data_start..offset.data_end], &orig[..]);
+        assert_eq!(offset.next_offset, buffer.len());
+    }
+
+    #[test]
+    fn unpack_two_at_once() {
+        let mut transport = Http::new(HOST);
+        let orig = (0..128).collect::<Vec<u8>>();
+        let mut buffer = response("200 OK", &orig);
+        let single_size = buffer.len();
+        buffer.extend(response("200 OK", &orig));
+
+        let offset = transport.unpack(&mut buffer[..]).unwrap();
+        assert_eq!(&buffer[offset.data_start..offset.data_end], &orig[..]);
+        assert_eq!(offset.next_offset, single_size);
+
+        let n = offset.next_offset;
+        let offset = transport.unpack(&mut buffer[n..]).unwrap();
+        assert_eq!(&buffer[n..][offset.data_start..offset.data_end], &orig[..]);
+    }
+
+    #[test]
+    fn unpack_bad_http_status() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = response("404 Not Found", &[]);
+        assert_eq!(
+            transport.unpack(&mut buffer[..]),
+            Err(Error::BadStatus { status: 404 })
+        );
+    }
+
+    #[test]
+    fn unpack_bad_status() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = response("200 OK", &(-404_i32).to_le_bytes());
+        assert_eq!(
+            transport.unpack(&mut buffer[..]),
+            Err(Error::BadStatus { status: 404 })
+        );
+    }
+
+    #[test]
+    fn unpack_bad_status_min() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = response("200 OK", &i32::MIN.to_le_bytes());
+        assert_eq!(
+            transport.unpack(&mut buffer[..]),
+            Err(Error::BadStatus {
+                status: i32::MIN.unsigned_abs()
+            })
+        );
+    }
+
+    #[test]
+    fn unpack_positive_short_body() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = response("200 OK", &404_i32.to_le_bytes());
+        assert_eq!(
+            transport.unpack(&mut buffer[..]),
+            Err(Error::BadLen { got: 4 })
+        );
+    }
+
+    #[test]
+    fn unpack_bad_header() {
+        let mut transport = Http::new(HOST);
+        let mut buffer = b"HTTP/1.1 200 OK\r\n\r\n".to_vec();
+        assert_eq!(transport.unpack(&mut buffer[..]), Err(Error::BadHeader));
+    }
+}
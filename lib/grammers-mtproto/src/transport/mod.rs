@@ -13,12 +13,14 @@
 //! [MTProto transports]: https://core.telegram.org/mtproto#mtproto-transport
 mod abridged;
 mod full;
+mod http;
 mod intermediate;
 mod obfuscated;
 
 pub use abridged::Abridged;
 pub use full::Full;
 use grammers_crypto::DequeBuffer;
+pub use http::Http;
 pub use intermediate::Intermediate;
 pub use obfuscated::Obfuscated;
 use std::fmt;
@@ -55,6 +57,9 @@ pub enum Error {
     /// [transport-level error]: https://core.telegram.org/mtproto/mtproto-transports#transport-errors
     /// [HTTP status code]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status
     BadStatus { status: u32 },
+
+    /// The headers of a HTTP response could not be parsed, or lacked required values.
+    BadHeader,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -81,6 +86,7 @@ impl fmt::Display for Error {
             Error::BadStatus { status } => {
                 write!(f, "bad status (negative length -{status})")
             }
+            Error::BadHeader => write!(f, "bad http header"),
         }
     }
 }
@@ -99,6 +105,14 @@ pub trait Transport {
 
     /// Reset the state, as if a new instance was just created.
     fn reset(&mut self);
+
+    /// Whether the server can only send data in response to a request.
+    ///
+    /// When this is `true`, a request must be kept open at all times for the
+    /// server to be able to deliver updates (also known as long polling).
+    fn requires_polling(&self) -> bool {
+        false
+    }
 }
 
 /// The trait used by the obfuscated transport to get the transport tags.
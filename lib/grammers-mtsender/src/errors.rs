@@ -162,6 +162,20 @@ impl RpcError {
     }
 }
 
+/// This error occurs when a request cannot be enqueued because too many requests are pending.
+///
+/// The request may be enqueued again after some of the pending requests complete.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueFull;
+
+impl std::error::Error for QueueFull {}
+
+impl fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request queue is full")
+    }
+}
+
 /// This error occurs when a Remote Procedure call was unsuccessful.
 ///
 /// The request should be retransmited when this happens, unless the
@@ -175,6 +189,9 @@ pub enum InvocationError {
     /// The request was cancelled or dropped, and the results won't arrive.
     Dropped,
 
+    /// The request did not complete in time, and was cancelled.
+    Timeout,
+
     /// The error occured while reading the response.
     Read(ReadError),
 }
@@ -186,6 +203,7 @@ impl fmt::Display for InvocationError {
         match self {
             Self::Rpc(err) => write!(f, "request error: {err}"),
             Self::Dropped => write!(f, "request error: dropped (cancelled)"),
+            Self::Timeout => write!(f, "request error: timed out"),
             Self::Read(err) => write!(f, "request error: {err}"),
         }
     }
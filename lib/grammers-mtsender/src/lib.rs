@@ -11,10 +11,11 @@
 mod errors;
 mod net;
 mod reconnection;
+mod stats;
 pub mod utils;
 
 pub use crate::reconnection::*;
-pub use errors::{AuthorizationError, InvocationError, ReadError, RpcError};
+pub use errors::{AuthorizationError, InvocationError, QueueFull, ReadError, RpcError};
 use futures_util::future::{Either, pending, select};
 use grammers_crypto::DequeBuffer;
 use grammers_mtproto::mtp::{
@@ -22,21 +23,24 @@ use grammers_mtproto::mtp::{
 };
 use grammers_mtproto::transport::{self, Transport};
 use grammers_mtproto::{MsgId, authentication};
-use grammers_tl_types::{self as tl, Deserializable, RemoteCall};
+use grammers_tl_types::{self as tl, Deserializable, Identifiable, RemoteCall};
 use log::{debug, error, info, trace, warn};
 use net::NetStream;
 pub use net::ServerAddr;
+pub use stats::{ConnectionStats, LATENCY_BUCKETS, LatencyHistogram, MethodStats, Stats};
+use std::cmp::Reverse;
 use std::io;
 use std::io::Error;
 use std::ops::ControlFlow;
 use std::pin::pin;
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::time::Duration;
 use tl::Serializable;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::error::TryRecvError;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
 use utils::{sleep, sleep_until};
 use web_time::{Instant, SystemTime};
 
@@ -69,6 +73,12 @@ const PING_DELAY: Duration = Duration::from_secs(60);
 /// are getting through consistently enough.
 const NO_PING_DISCONNECT: i32 = 75;
 
+/// How long, in milliseconds, should the server wait for messages to arrive before responding to
+/// a long poll when using a transport that [requires polling].
+///
+/// [requires polling]: Transport::requires_polling
+const HTTP_MAX_WAIT: i32 = 25_000;
+
 /// Generate a "random" ping ID.
 pub(crate) fn generate_random_id() -> i64 {
     static LAST_ID: AtomicI64 = AtomicI64::new(0);
@@ -87,6 +97,12 @@ pub(crate) fn generate_random_id() -> i64 {
     LAST_ID.fetch_add(1, Ordering::SeqCst)
 }
 
+/// Read the constructor identifier of the serialized request.
+fn request_id(body: &[u8]) -> u32 {
+    assert!(body.len() >= 4);
+    u32::from_le_bytes([body[0], body[1], body[2], body[3]])
+}
+
 /// Manages enqueuing requests, matching them to their response, and IO.
 pub struct Sender<T: Transport, M: Mtp> {
     stream: NetStream,
@@ -97,6 +113,11 @@ pub struct Sender<T: Transport, M: Mtp> {
     request_rx: mpsc::UnboundedReceiver<Request>,
     next_ping: Instant,
     reconnection_policy: &'static dyn ReconnectionPolicy,
+    stats: Stats,
+
+    // How many packets were sent that have not received a response yet.
+    // Only tracked when the transport requires polling.
+    pending_polls: usize,
 
     // Transport-level buffers and positions
     read_buffer: Vec<u8>,
@@ -109,6 +130,55 @@ struct Request {
     body: Vec<u8>,
     state: RequestState,
     result: oneshot::Sender<Result<Vec<u8>, InvocationError>>,
+    // Requests made by the sender itself, such as pings, have nobody waiting for their result,
+    // so they must not be considered cancelled when the receiving end of the channel is dropped.
+    internal: bool,
+    priority: Priority,
+    chain: Option<RequestChain>,
+    // When the request was last sent, used to measure how long it takes to get a response.
+    sent_at: Option<Instant>,
+    // Held for as long as the request is alive to free up space in the `Enqueuer` when dropped.
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+/// The priority of a request, used to determine which requests are sent first.
+///
+/// Requests with a higher priority are always serialized before those with a lower priority,
+/// and are not subject to the [`Enqueuer`] limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Bulk requests, such as downloading files or fetching large amounts of history, which
+    /// should not slow down other requests.
+    Low,
+    /// Requests made by most methods.
+    #[default]
+    Normal,
+    /// Requests that should be sent as soon as possible, such as pings. These requests bypass
+    /// the limit on how many requests may be enqueued.
+    High,
+}
+
+/// Identifies a sequence of requests which Telegram must process in the same order in which they
+/// were enqueued, even if they are sent at the same time without waiting for each response.
+///
+/// When a request that belongs to a chain is serialized, it is wrapped in `invokeAfterMsg`,
+/// referring to the previous request in the same chain, unless said request already completed.
+/// If the previous request was not serialized yet, the request will wait for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RequestChain(u64);
+
+impl RequestChain {
+    /// Create a new, empty chain of requests, which is unrelated to any other chain.
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for RequestChain {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -123,7 +193,10 @@ enum RequestState {
     Sent(MsgIdPair),
 }
 
-pub struct Enqueuer(mpsc::UnboundedSender<Request>);
+pub struct Enqueuer {
+    tx: mpsc::UnboundedSender<Request>,
+    permits: Option<Arc<Semaphore>>,
+}
 
 impl MsgIdPair {
     fn new(msg_id: MsgId) -> Self {
@@ -135,25 +208,133 @@ impl MsgIdPair {
 }
 
 impl Enqueuer {
+    /// Limit how many requests may be alive at once, including both the requests waiting to be
+    /// sent and those waiting for a response. Requests with [`Priority::High`] are not counted.
+    ///
+    /// The limit only applies to requests enqueued with [`Enqueuer::try_enqueue`] and similar
+    /// methods. By default, there is no limit, and a limit of zero (`0`) also disables it.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.permits = (limit != 0).then(|| Arc::new(Semaphore::new(limit)));
+        self
+    }
+
     /// Enqueue a Remote Procedure Call to be sent in future calls to `step`.
+    ///
+    /// This method ignores the [limit], so the queue may grow past it.
+    /// See [`Enqueuer::try_enqueue_with_priority`] for details.
+    ///
+    /// [limit]: Enqueuer::with_limit
     pub fn enqueue<R: RemoteCall>(
         &self,
         request: &R,
     ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
-        // TODO we probably want a bound here (to not enqueue more than N at once)
+        self.enqueue_with_priority(request, Priority::Normal)
+    }
+
+    /// Like [`Enqueuer::enqueue`], but with the given priority instead of [`Priority::Normal`].
+    pub fn enqueue_with_priority<R: RemoteCall>(
+        &self,
+        request: &R,
+        priority: Priority,
+    ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
+        self.enqueue_request(request, priority, None, None)
+    }
+
+    /// Like [`Enqueuer::enqueue_with_priority`], but Telegram will only process the request
+    /// after all the requests previously enqueued in the same [`RequestChain`].
+    ///
+    /// Requests in the same chain should be enqueued with the same priority. Otherwise, a request
+    /// with a higher priority will still wait for the previous ones in the chain to be sent.
+    pub fn enqueue_in_chain<R: RemoteCall>(
+        &self,
+        request: &R,
+        priority: Priority,
+        chain: RequestChain,
+    ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
+        self.enqueue_request(request, priority, Some(chain), None)
+    }
+
+    /// Like [`Enqueuer::enqueue`], but subject to the [limit].
+    ///
+    /// See [`Enqueuer::try_enqueue_with_priority`] for details.
+    ///
+    /// [limit]: Enqueuer::with_limit
+    pub fn try_enqueue<R: RemoteCall>(
+        &self,
+        request: &R,
+    ) -> Result<oneshot::Receiver<Result<Vec<u8>, InvocationError>>, QueueFull> {
+        self.try_enqueue_with_priority(request, Priority::Normal)
+    }
+
+    /// Enqueue a Remote Procedure Call to be sent in future calls to `step`.
+    ///
+    /// Fails with [`QueueFull`] if the [limit] was reached. Calling `step` until some of the
+    /// pending requests complete will make room for more.
+    ///
+    /// Dropping the returned receiver cancels the request. If it was not sent yet, it will never
+    /// be sent. Otherwise, the server will be asked to drop the answer with `rpc_drop_answer`.
+    ///
+    /// [limit]: Enqueuer::with_limit
+    pub fn try_enqueue_with_priority<R: RemoteCall>(
+        &self,
+        request: &R,
+        priority: Priority,
+    ) -> Result<oneshot::Receiver<Result<Vec<u8>, InvocationError>>, QueueFull> {
+        let permit = self.acquire_permit(priority)?;
+        Ok(self.enqueue_request(request, priority, None, permit))
+    }
+
+    /// Like [`Enqueuer::enqueue_in_chain`], but subject to the [limit].
+    ///
+    /// [limit]: Enqueuer::with_limit
+    pub fn try_enqueue_in_chain<R: RemoteCall>(
+        &self,
+        request: &R,
+        priority: Priority,
+        chain: RequestChain,
+    ) -> Result<oneshot::Receiver<Result<Vec<u8>, InvocationError>>, QueueFull> {
+        let permit = self.acquire_permit(priority)?;
+        Ok(self.enqueue_request(request, priority, Some(chain), permit))
+    }
+
+    fn acquire_permit(
+        &self,
+        priority: Priority,
+    ) -> Result<Option<OwnedSemaphorePermit>, QueueFull> {
+        match &self.permits {
+            Some(permits) if priority != Priority::High => Arc::clone(permits)
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(|_| QueueFull),
+            _ => Ok(None),
+        }
+    }
+
+    fn enqueue_request<R: RemoteCall>(
+        &self,
+        request: &R,
+        priority: Priority,
+        chain: Option<RequestChain>,
+        permit: Option<OwnedSemaphorePermit>,
+    ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
         let body = request.to_bytes();
-        assert!(body.len() >= 4);
-        let req_id = u32::from_le_bytes([body[0], body[1], body[2], body[3]]);
+        let req_id = request_id(&body);
         debug!(
-            "enqueueing request {} to be serialized",
-            tl::name_for_id(req_id)
+            "enqueueing request {} to be serialized with {:?} priority",
+            tl::name_for_id(req_id),
+            priority
         );
 
         let (tx, rx) = oneshot::channel();
-        if let Err(err) = self.0.send(Request {
+        if let Err(err) = self.tx.send(Request {
             body,
             state: RequestState::NotSerialized,
             result: tx,
+            internal: false,
+            priority,
+            chain,
+            sent_at: None,
+            _permit: permit,
         }) {
             err.0.result.send(Err(InvocationError::Dropped)).unwrap();
         }
@@ -180,16 +361,24 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
                 request_rx: rx,
                 next_ping: Instant::now() + PING_DELAY,
                 reconnection_policy,
+                stats: Stats::default(),
+                pending_polls: 0,
 
                 read_buffer: vec![0; MAXIMUM_DATA],
                 read_tail: 0,
                 write_buffer: DequeBuffer::with_capacity(MAXIMUM_DATA, LEADING_BUFFER_SPACE),
                 write_head: 0,
             },
-            Enqueuer(tx),
+            Enqueuer { tx, permits: None },
         ))
     }
 
+    /// Return a handle to the statistics of this sender, which remains usable while the sender
+    /// is busy stepping.
+    pub fn stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
     pub async fn invoke<R: RemoteCall>(&mut self, request: &R) -> Result<Vec<u8>, InvocationError> {
         let rx = self.enqueue_body(request.to_bytes());
         self.step_until_receive(rx).await
@@ -205,8 +394,20 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
         &mut self,
         body: Vec<u8>,
     ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
-        assert!(body.len() >= 4);
-        let req_id = u32::from_le_bytes([body[0], body[1], body[2], body[3]]);
+        self.enqueue_request(body, false)
+    }
+
+    /// Like `enqueue_body`, but the result is not of interest and the request cannot be cancelled.
+    fn enqueue_internal(&mut self, body: Vec<u8>) {
+        drop(self.enqueue_request(body, true));
+    }
+
+    fn enqueue_request(
+        &mut self,
+        body: Vec<u8>,
+        internal: bool,
+    ) -> oneshot::Receiver<Result<Vec<u8>, InvocationError>> {
+        let req_id = request_id(&body);
         debug!(
             "enqueueing request {} to be serialized",
             tl::name_for_id(req_id)
@@ -217,6 +418,15 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
             body,
             state: RequestState::NotSerialized,
             result: tx,
+            internal,
+            priority: if internal {
+                Priority::High
+            } else {
+                Priority::Normal
+            },
+            chain: None,
+            sent_at: None,
+            _permit: None,
         });
         rx
     }
@@ -248,6 +458,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
             Write(io::Result<usize>),
         }
 
+        self.drop_cancelled_requests();
         self.try_fill_write();
         let write_len = self.write_buffer.len() - self.write_head;
         trace!(
@@ -293,9 +504,37 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
             }
         };
 
-        match res {
+        let res = match res {
             Ok(ok) => Ok(ok),
             Err(err) => self.on_error(err).await,
+        };
+        self.update_request_counts();
+        res
+    }
+
+    /// Update the statistics with the current number of pending requests.
+    fn update_request_counts(&mut self) {
+        let (mut queued, mut in_flight) = (0, 0);
+        for request in self.requests.iter().filter(|r| !r.internal) {
+            match request.state {
+                RequestState::NotSerialized | RequestState::Serialized(_) => queued += 1,
+                RequestState::Sent(_) => in_flight += 1,
+            }
+        }
+        self.stats.set_request_counts(queued, in_flight);
+    }
+
+    /// Record how long the request took to complete, since it was last sent.
+    fn record_latency(&self, request: &Request) {
+        let Some(sent_at) = request.sent_at else {
+            return;
+        };
+        let latency = sent_at.elapsed();
+        let req_id = request_id(&request.body);
+        self.stats.record_latency(tl::name_for_id(req_id), latency);
+        if req_id == tl::functions::PingDelayDisconnect::CONSTRUCTOR_ID {
+            debug!("keepalive ping took {:?}", latency);
+            self.stats.record_rtt(latency);
         }
     }
 
@@ -334,42 +573,123 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
         }
     }
 
+    /// Remove the requests whose result is no longer of interest.
+    ///
+    /// Requests that were not sent yet are simply forgotten. Requests that were already sent
+    /// are forgotten too, but the server is also asked to not send their response.
+    fn drop_cancelled_requests(&mut self) {
+        for i in (0..self.requests.len()).rev() {
+            let request = &self.requests[i];
+            if request.internal || !request.result.is_closed() {
+                continue;
+            }
+            match &request.state {
+                RequestState::NotSerialized => {
+                    debug!("dropping cancelled request before it was sent");
+                    self.requests.remove(i);
+                }
+                // The request is in the write buffer. It will be dropped once sent.
+                RequestState::Serialized(_) => {}
+                RequestState::Sent(pair) => {
+                    debug!("dropping answer to cancelled request {:?}", pair.msg_id);
+                    let body = tl::functions::RpcDropAnswer {
+                        req_msg_id: pair.msg_id.value(),
+                    }
+                    .to_bytes();
+                    self.requests.remove(i);
+                    self.enqueue_internal(body);
+                }
+            }
+        }
+    }
+
+    /// Find the message identifier of the request that must be processed before the request at
+    /// index `i`, if it belongs to a [`RequestChain`] and the previous request is still alive.
+    ///
+    /// Fails if the previous request has not been serialized yet, as there is no message
+    /// identifier to refer to. Requests are kept in the order they were enqueued, so the previous
+    /// request in the chain is always found before `i`.
+    fn previous_in_chain(&self, i: usize) -> Result<Option<MsgId>, ()> {
+        let Some(chain) = self.requests[i].chain else {
+            return Ok(None);
+        };
+        match self.requests[..i]
+            .iter()
+            .rev()
+            .find(|r| r.chain == Some(chain))
+            .map(|r| &r.state)
+        {
+            None => Ok(None),
+            Some(RequestState::NotSerialized) => Err(()),
+            Some(RequestState::Serialized(pair) | RequestState::Sent(pair)) => {
+                Ok(Some(pair.msg_id))
+            }
+        }
+    }
+
     /// Setup the write buffer for the transport, unless a write is already pending.
     fn try_fill_write(&mut self) {
         if !self.write_buffer.is_empty() {
             return;
         }
 
+        // Higher priority requests go first. The sort is stable, so requests with the same
+        // priority retain their relative order.
+        let mut pending = (0..self.requests.len())
+            .filter(|&i| matches!(self.requests[i].state, RequestState::NotSerialized))
+            .collect::<Vec<_>>();
+        pending.sort_by_key(|&i| Reverse(self.requests[i].priority));
+
         // TODO add a test to make sure we only ever send the same request once
-        for request in self
-            .requests
-            .iter_mut()
-            .filter(|r| matches!(r.state, RequestState::NotSerialized))
-        {
+        for i in pending {
+            let Ok(after) = self.previous_in_chain(i) else {
+                // Cannot be sent until the request it depends on is.
+                continue;
+            };
+
+            let request = &self.requests[i];
             // TODO make mtp itself use BytesMut to avoid copies
-            if let Some(msg_id) = self.mtp.push(&mut self.write_buffer, &request.body) {
-                assert!(request.body.len() >= 4);
-                let req_id = u32::from_le_bytes([
-                    request.body[0],
-                    request.body[1],
-                    request.body[2],
-                    request.body[3],
-                ]);
+            let msg_id = match after {
+                Some(after) => {
+                    let mut body = Vec::with_capacity(12 + request.body.len());
+                    tl::functions::InvokeAfterMsg::<()>::CONSTRUCTOR_ID.serialize(&mut body);
+                    after.value().serialize(&mut body);
+                    body.extend_from_slice(&request.body);
+                    self.mtp.push(&mut self.write_buffer, &body)
+                }
+                None => self.mtp.push(&mut self.write_buffer, &request.body),
+            };
+
+            if let Some(msg_id) = msg_id {
+                let req_id = request_id(&request.body);
                 debug!(
-                    "serialized request {:x} ({}) with {:?}",
+                    "serialized request {:x} ({}) with {:?} after {:?}",
                     req_id,
                     tl::name_for_id(req_id),
-                    msg_id
+                    msg_id,
+                    after
                 );
                 // Note how only NotSerialized become Serialized.
                 // Nasty bugs that take ~2h to find occur otherwise!
                 // (e.g. infinite loops leading to transport flood.)
-                request.state = RequestState::Serialized(MsgIdPair::new(msg_id));
+                self.requests[i].state = RequestState::Serialized(MsgIdPair::new(msg_id));
             } else {
                 break;
             }
         }
 
+        // If the server can only respond to requests, make sure there's always one it can
+        // respond to, so that it is able to deliver updates as soon as they occur.
+        if self.write_buffer.is_empty()
+            && self.pending_polls == 0
+            && self.transport.requires_polling()
+            && let Some(msg_id) = self
+                .mtp
+                .push_http_wait(&mut self.write_buffer, HTTP_MAX_WAIT)
+        {
+            debug!("serialized long poll with {:?}", msg_id);
+        }
+
         if let Some(container_msg_id) = self.mtp.finalize(&mut self.write_buffer) {
             for request in self.requests.iter_mut() {
                 match request.state {
@@ -395,6 +715,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
         }
 
         self.read_tail += n;
+        self.stats.record_read(n);
         trace!("read {} bytes from the network", n);
         trace!("trying to unpack buffer of {} bytes...", self.read_tail);
 
@@ -409,6 +730,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
             {
                 Ok(offset) => {
                     debug!("deserializing valid transport packet...");
+                    self.pending_polls = self.pending_polls.saturating_sub(1);
                     let result = self.mtp.deserialize(
                         &self.read_buffer[next_offset..][offset.data_start..offset.data_end],
                     )?;
@@ -430,6 +752,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
     /// Handle `n` more written bytes being ready to process by the transport.
     fn on_net_write(&mut self, n: usize) {
         self.write_head += n;
+        self.stats.record_written(n);
         trace!(
             "written {} bytes to the network ({}/{})",
             n,
@@ -443,12 +766,17 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
 
         self.write_buffer.clear();
         self.write_head = 0;
+        if self.transport.requires_polling() {
+            self.pending_polls += 1;
+        }
+        let now = Instant::now();
         for req in self.requests.iter_mut() {
             match &req.state {
                 RequestState::NotSerialized | RequestState::Sent(_) => {}
                 RequestState::Serialized(pair) => {
                     debug!("sent request with {:?}", pair);
                     req.state = RequestState::Sent(pair.clone());
+                    req.sent_at = Some(now);
                 }
             }
         }
@@ -458,14 +786,12 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
     fn on_ping_timeout(&mut self) {
         let ping_id = generate_random_id();
         debug!("enqueueing keepalive ping {}", ping_id);
-        drop(
-            self.enqueue_body(
-                tl::functions::PingDelayDisconnect {
-                    ping_id,
-                    disconnect_delay: NO_PING_DISCONNECT,
-                }
-                .to_bytes(),
-            ),
+        self.enqueue_internal(
+            tl::functions::PingDelayDisconnect {
+                ping_id,
+                disconnect_delay: NO_PING_DISCONNECT,
+            }
+            .to_bytes(),
         );
         self.next_ping = Instant::now() + PING_DELAY;
     }
@@ -486,6 +812,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
         self.read_buffer.fill(0);
         self.write_head = 0;
         self.write_buffer.clear();
+        self.pending_polls = 0;
 
         let error = match error {
             ReadError::Io(_)
@@ -585,6 +912,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
 
     fn process_result(&mut self, result: RpcResult) {
         if let Some(req) = self.pop_request(result.msg_id) {
+            self.record_latency(&req);
             let x = result.body;
             assert!(x.len() >= 4);
             let res_id = u32::from_le_bytes([x[0], x[1], x[2], x[3]]);
@@ -606,13 +934,12 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
     fn process_error(&mut self, error: RpcResultError) {
         if let Some(req) = self.pop_request(error.msg_id) {
             debug!("got rpc error {:?}", error.error);
-            let x = req.body.as_slice();
-            drop(
-                req.result.send(Err(InvocationError::Rpc(
-                    RpcError::from(error.error)
-                        .with_caused_by(u32::from_le_bytes([x[0], x[1], x[2], x[3]])),
-                ))),
-            );
+            self.record_latency(&req);
+            let req_id = request_id(&req.body);
+            let error = RpcError::from(error.error).with_caused_by(req_id);
+            self.stats
+                .record_rpc_error(tl::name_for_id(req_id), &error.name);
+            drop(req.result.send(Err(InvocationError::Rpc(error))));
         } else {
             info!(
                 "got rpc error {:?} but no such request is saved",
@@ -660,7 +987,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
                                 pair.msg_id
                             );
                         }
-                        let req = self.requests.swap_remove(i);
+                        let req = self.requests.remove(i);
                         drop(req.result.send(Err(InvocationError::Dropped)));
                     }
                 }
@@ -691,7 +1018,7 @@ impl<T: Transport, M: Mtp> Sender<T, M> {
                     panic!("got response {msg_id:?} for unsent request {pair:?}");
                 }
                 RequestState::Sent(pair) if pair.msg_id == msg_id => {
-                    return Some(self.requests.swap_remove(i));
+                    return Some(self.requests.remove(i));
                 }
                 _ => {}
             }
@@ -757,6 +1084,8 @@ pub async fn generate_auth_key<T: Transport>(
             write_head: sender.write_head,
             addr: sender.addr,
             reconnection_policy: sender.reconnection_policy,
+            stats: sender.stats,
+            pending_polls: sender.pending_polls,
         },
         enqueuer,
     ))
@@ -776,3 +1105,243 @@ pub async fn connect_with_auth<T: Transport>(
     )
     .await
 }
+
+#[cfg(all(test, not(all(target_arch = "wasm32", target_os = "unknown"))))]
+mod tests {
+    use super::*;
+    use grammers_mtproto::mtp::DeserializeError;
+    use tokio::net::TcpListener;
+    use tokio::runtime;
+
+    /// Encrypts messages as usual, but remembers what was pushed and with which message ID.
+    struct RecordingMtp {
+        inner: mtp::Encrypted,
+        pushed: Vec<(MsgId, Vec<u8>)>,
+    }
+
+    impl Mtp for RecordingMtp {
+        fn push(&mut self, buffer: &mut DequeBuffer<u8>, request: &[u8]) -> Option<MsgId> {
+            let msg_id = self.inner.push(buffer, request)?;
+            self.pushed.push((msg_id, request.to_vec()));
+            Some(msg_id)
+        }
+
+        fn finalize(&mut self, buffer: &mut DequeBuffer<u8>) -> Option<MsgId> {
+            self.inner.finalize(buffer)
+        }
+
+        fn deserialize(
+            &mut self,
+            payload: &[u8],
+        ) -> Result<Vec<Deserialization>, DeserializeError> {
+            self.inner.deserialize(payload)
+        }
+
+        fn reset(&mut self) {
+            self.inner.reset()
+        }
+    }
+
+    type TestSender = Sender<transport::Intermediate, RecordingMtp>;
+
+    /// Run the test with a sender connected to a local server that never responds.
+    fn with_sender<F: AsyncFnOnce(TestSender, Enqueuer)>(test: F) {
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let (sender, enqueuer) = Sender::connect(
+                transport::Intermediate::new(),
+                RecordingMtp {
+                    inner: mtp::Encrypted::build().finish([0; 256]),
+                    pushed: Vec::new(),
+                },
+                ServerAddr::Tcp {
+                    address: listener.local_addr().unwrap(),
+                },
+                &NoReconnect,
+            )
+            .await
+            .unwrap();
+            test(sender, enqueuer).await
+        });
+    }
+
+    fn ping(ping_id: i64) -> tl::functions::Ping {
+        tl::functions::Ping { ping_id }
+    }
+
+    /// Move the requests from the enqueuer into the sender, as `step` would.
+    fn receive_requests(sender: &mut TestSender) {
+        while let Ok(request) = sender.request_rx.try_recv() {
+            sender.requests.push(request);
+        }
+    }
+
+    /// Serialize as many requests as possible and pretend they were all written to the network.
+    fn flush(sender: &mut TestSender) {
+        receive_requests(sender);
+        sender.try_fill_write();
+        let n = sender.write_buffer.len();
+        if n != 0 {
+            sender.on_net_write(n);
+        }
+    }
+
+    fn pushed_bodies(sender: &TestSender) -> Vec<Vec<u8>> {
+        sender
+            .mtp
+            .pushed
+            .iter()
+            .map(|(_, body)| body.clone())
+            .collect()
+    }
+
+    #[test]
+    fn higher_priority_serialized_first() {
+        with_sender(async |mut sender, enqueuer| {
+            let _rx = [
+                enqueuer.enqueue_with_priority(&ping(1), Priority::Low),
+                enqueuer.enqueue_with_priority(&ping(2), Priority::Low),
+                enqueuer.enqueue_with_priority(&ping(3), Priority::Normal),
+                enqueuer.enqueue_with_priority(&ping(4), Priority::High),
+                enqueuer.enqueue_with_priority(&ping(5), Priority::Normal),
+            ];
+            flush(&mut sender);
+
+            assert_eq!(
+                pushed_bodies(&sender),
+                [4, 3, 5, 1, 2].map(|id| ping(id).to_bytes())
+            );
+        });
+    }
+
+    #[test]
+    fn limit_rejects_until_request_completes() {
+        with_sender(async |mut sender, enqueuer| {
+            let enqueuer = enqueuer.with_limit(1);
+            let mut rx = enqueuer.try_enqueue(&ping(1)).unwrap();
+            assert_eq!(enqueuer.try_enqueue(&ping(2)).err(), Some(QueueFull));
+            // High priority requests are not limited.
+            let _high = enqueuer
+                .try_enqueue_with_priority(&ping(3), Priority::High)
+                .unwrap();
+            // Nor are those enqueued with the infallible methods.
+            let _unlimited = enqueuer.enqueue(&ping(4));
+
+            flush(&mut sender);
+            // The high priority request was serialized first.
+            let (msg_id, _) = sender.mtp.pushed[1];
+            sender.process_result(RpcResult {
+                msg_id,
+                body: tl::types::Pong {
+                    msg_id: 0,
+                    ping_id: 1,
+                }
+                .to_bytes(),
+            });
+
+            assert!(matches!(rx.try_recv(), Ok(Ok(_))));
+            assert!(enqueuer.try_enqueue(&ping(2)).is_ok());
+        });
+    }
+
+    #[test]
+    fn chain_invokes_after_previous() {
+        with_sender(async |mut sender, enqueuer| {
+            let chain = RequestChain::new();
+            let _rx = [
+                enqueuer.enqueue_in_chain(&ping(1), Priority::Normal, chain),
+                enqueuer.enqueue_in_chain(&ping(2), Priority::Normal, chain),
+            ];
+            flush(&mut sender);
+
+            let (first_msg_id, _) = sender.mtp.pushed[0];
+            assert_eq!(
+                pushed_bodies(&sender),
+                [
+                    ping(1).to_bytes(),
+                    tl::functions::InvokeAfterMsg {
+                        msg_id: first_msg_id.value(),
+                        query: ping(2),
+                    }
+                    .to_bytes(),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn chain_waits_for_previous_to_serialize() {
+        with_sender(async |mut sender, enqueuer| {
+            let chain = RequestChain::new();
+            let _rx = [
+                enqueuer.enqueue_in_chain(&ping(1), Priority::Low, chain),
+                enqueuer.enqueue_in_chain(&ping(2), Priority::High, chain),
+            ];
+
+            // The second request goes first by priority, but it has no message to refer to yet.
+            receive_requests(&mut sender);
+            sender.try_fill_write();
+            assert_eq!(pushed_bodies(&sender), [ping(1).to_bytes()]);
+            assert!(matches!(
+                sender.requests[1].state,
+                RequestState::NotSerialized
+            ));
+
+            let n = sender.write_buffer.len();
+            sender.on_net_write(n);
+            flush(&mut sender);
+
+            let (first_msg_id, _) = sender.mtp.pushed[0];
+            assert_eq!(
+                pushed_bodies(&sender)[1],
+                tl::functions::InvokeAfterMsg {
+                    msg_id: first_msg_id.value(),
+                    query: ping(2),
+                }
+                .to_bytes()
+            );
+        });
+    }
+
+    #[test]
+    fn cancelled_unsent_request_is_forgotten() {
+        with_sender(async |mut sender, enqueuer| {
+            let rx = enqueuer.enqueue(&ping(1));
+            receive_requests(&mut sender);
+            drop(rx);
+
+            sender.drop_cancelled_requests();
+            assert!(sender.requests.is_empty());
+
+            flush(&mut sender);
+            assert!(sender.mtp.pushed.is_empty());
+        });
+    }
+
+    #[test]
+    fn cancelled_sent_request_drops_answer() {
+        with_sender(async |mut sender, enqueuer| {
+            let rx = enqueuer.enqueue(&ping(1));
+            flush(&mut sender);
+            let (msg_id, _) = sender.mtp.pushed[0];
+            drop(rx);
+
+            sender.drop_cancelled_requests();
+            assert_eq!(sender.requests.len(), 1);
+            assert!(sender.requests[0].internal);
+
+            flush(&mut sender);
+            assert_eq!(
+                pushed_bodies(&sender)[1],
+                tl::functions::RpcDropAnswer {
+                    req_msg_id: msg_id.value(),
+                }
+                .to_bytes()
+            );
+        });
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright 2020 - developers of the `grammers` project.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The upper bounds of each bucket used by [`LatencyHistogram`].
+///
+/// Latencies larger than the last bound are counted in an additional, unbounded bucket.
+pub const LATENCY_BUCKETS: [Duration; 10] = [
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+];
+
+/// A histogram of how long requests took to complete, from the moment they were sent until
+/// their response was received.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    counts: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: Duration,
+}
+
+/// Statistics about a single method, such as `messages.sendMessage`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MethodStats {
+    /// How long did requests for this method take to complete.
+    pub latency: LatencyHistogram,
+    /// How many requests for this method failed with a RPC error.
+    pub rpc_errors: u64,
+}
+
+/// A snapshot of the health of a connection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// The round-trip time measured by the last keepalive ping, if one completed yet.
+    pub rtt: Option<Duration>,
+    /// Total bytes read from the network, including transport overhead.
+    pub bytes_read: u64,
+    /// Total bytes written to the network, including transport overhead.
+    pub bytes_written: u64,
+    /// Requests waiting to be sent.
+    pub queued: usize,
+    /// Requests that were sent and are waiting for a response.
+    pub in_flight: usize,
+    /// Statistics for each method that completed at least once, indexed by its name.
+    pub methods: HashMap<&'static str, MethodStats>,
+    /// How many times each RPC error occurred, indexed by its name.
+    pub rpc_errors: HashMap<String, u64>,
+}
+
+/// A handle to the statistics of a [`Sender`](crate::Sender), which can be used to take
+/// [snapshots](Stats::snapshot) of them at any time, even while the sender is busy.
+#[derive(Clone, Debug, Default)]
+pub struct Stats(Arc<Mutex<ConnectionStats>>);
+
+impl LatencyHistogram {
+    /// Record a new sample.
+    pub fn record(&mut self, latency: Duration) {
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| latency <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.counts[bucket] += 1;
+        self.sum += latency;
+    }
+
+    /// How many samples were recorded.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The sum of all the samples recorded.
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// The average latency, if any sample was recorded.
+    pub fn mean(&self) -> Option<Duration> {
+        match self.count() {
+            0 => None,
+            n => Some(self.sum / n as u32),
+        }
+    }
+
+    /// Iterate over the upper bound of each bucket along with how many samples fell in it.
+    ///
+    /// The last bucket has no upper bound.
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        LATENCY_BUCKETS
+            .iter()
+            .copied()
+            .map(Some)
+            .chain(std::iter::once(None))
+            .zip(self.counts.iter().copied())
+    }
+
+    /// Estimate the latency below which the given fraction of samples fall, as the upper bound of
+    /// the bucket containing said quantile (so `0.99` is the 99th percentile).
+    ///
+    /// Returns `None` if no samples were recorded, or if the quantile is in the unbounded bucket.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let target = ((count as f64) * q.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bound, n) in self.buckets() {
+            seen += n;
+            if seen >= target {
+                return bound;
+            }
+        }
+        None
+    }
+}
+
+impl Stats {
+    /// Take a snapshot of the current statistics.
+    pub fn snapshot(&self) -> ConnectionStats {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub(crate) fn record_read(&self, n: usize) {
+        self.0.lock().unwrap().bytes_read += n as u64;
+        #[cfg(feature = "metrics")]
+        metrics::counter!("grammers_bytes_read_total").increment(n as u64);
+    }
+
+    pub(crate) fn record_written(&self, n: usize) {
+        self.0.lock().unwrap().bytes_written += n as u64;
+        #[cfg(feature = "metrics")]
+        metrics::counter!("grammers_bytes_written_total").increment(n as u64);
+    }
+
+    pub(crate) fn record_rtt(&self, rtt: Duration) {
+        self.0.lock().unwrap().rtt = Some(rtt);
+        #[cfg(feature = "metrics")]
+        metrics::gauge!("grammers_rtt_seconds").set(rtt.as_secs_f64());
+    }
+
+    pub(crate) fn record_latency(&self, method: &'static str, latency: Duration) {
+        self.0
+            .lock()
+            .unwrap()
+            .methods
+            .entry(method)
+            .or_default()
+            .latency
+            .record(latency);
+        #[cfg(feature = "metrics")]
+        metrics::histogram!("grammers_request_duration_seconds", "method" => method)
+            .record(latency.as_secs_f64());
+    }
+
+    pub(crate) fn record_rpc_error(&self, method: &'static str, error: &str) {
+        let mut stats = self.0.lock().unwrap();
+        stats.methods.entry(method).or_default().rpc_errors += 1;
+        *stats.rpc_errors.entry(error.to_string()).or_default() += 1;
+        #[cfg(feature = "metrics")]
+        metrics::counter!("grammers_rpc_errors_total", "method" => method, "error" => error.to_string())
+            .increment(1);
+    }
+
+    pub(crate) fn set_request_counts(&self, queued: usize, in_flight: usize) {
+        let mut stats = self.0.lock().unwrap();
+        stats.queued = queued;
+        stats.in_flight = in_flight;
+        #[cfg(feature = "metrics")]
+        {
+            metrics::gauge!("grammers_requests_queued").set(queued as f64);
+            metrics::gauge!("grammers_requests_in_flight").set(in_flight as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_empty() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.mean(), None);
+        assert_eq!(histogram.quantile(0.5), None);
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let mut histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_millis(10));
+        histogram.record(Duration::from_millis(25));
+        histogram.record(Duration::from_millis(300));
+        histogram.record(Duration::from_secs(60));
+
+        let buckets = histogram.buckets().collect::<Vec<_>>();
+        assert_eq!(buckets.len(), LATENCY_BUCKETS.len() + 1);
+        assert_eq!(buckets[0], (Some(Duration::from_millis(25)), 2));
+        assert_eq!(buckets[4], (Some(Duration::from_millis(500)), 1));
+        assert_eq!(buckets[LATENCY_BUCKETS.len()], (None, 1));
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.sum(), Duration::from_millis(60_335));
+    }
+
+    #[test]
+    fn histogram_quantiles() {
+        let mut histogram = LatencyHistogram::default();
+        for _ in 0..99 {
+            histogram.record(Duration::from_millis(40));
+        }
+        histogram.record(Duration::from_secs(2));
+
+        assert_eq!(histogram.quantile(0.5), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.quantile(0.99), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.quantile(1.0), Some(Duration::from_millis(2500)));
+        assert_eq!(histogram.quantile(0.0), Some(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn stats_rpc_errors() {
+        let stats = Stats::default();
+        stats.record_rpc_error("messages.sendMessage", "FLOOD_WAIT");
+        stats.record_rpc_error("messages.sendMessage", "FLOOD_WAIT");
+        stats.record_rpc_error("users.getUsers", "USER_ID_INVALID");
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.methods["messages.sendMessage"].rpc_errors, 2);
+        assert_eq!(snapshot.methods["users.getUsers"].rpc_errors, 1);
+        assert_eq!(snapshot.rpc_errors["FLOOD_WAIT"], 2);
+        assert_eq!(snapshot.rpc_errors["USER_ID_INVALID"], 1);
+    }
+}